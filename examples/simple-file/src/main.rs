@@ -152,11 +152,11 @@ fn main() -> std::io::Result<()> {
     let record_param_d_updated = producer.parameter_f32("D", 2.01).unwrap();
     let record_param_servo_trim = producer.parameter_i32("SERVO_TRIM", 1500).unwrap();
 
-    let record_log_info_tagged = producer.log_tagged(LogLevel::Info, 1, timestamp, "info tagged log");
-    let record_log_debug = producer.log(LogLevel::Debug, timestamp, "This is debug log");
-    let record_log_emerg = producer.log(LogLevel::Emerg, timestamp, "This is Emerg log");
-    let record_log_alert = producer.log(LogLevel::Alert, timestamp, "This is Alert log");
-    let record_log_notice = producer.log(LogLevel::Notice, timestamp, "This is Notice log");
+    let record_log_info_tagged = producer.try_log_tagged(LogLevel::Info, 1, timestamp, "info tagged log");
+    let record_log_debug = producer.try_log(LogLevel::Debug, timestamp, "This is debug log");
+    let record_log_emerg = producer.try_log(LogLevel::Emerg, timestamp, "This is Emerg log");
+    let record_log_alert = producer.try_log(LogLevel::Alert, timestamp, "This is Alert log");
+    let record_log_notice = producer.try_log(LogLevel::Notice, timestamp, "This is Notice log");
 
     exporter.accept(record_param_p_initial).map_err(map_export_error)?;
     exporter.accept(record_param_i_initial).map_err(map_export_error)?;
@@ -174,13 +174,19 @@ fn main() -> std::io::Result<()> {
         .accept(record_param_servo_trim)
         .map_err(map_export_error)?;
 
-    exporter
-        .accept(record_log_info_tagged)
-        .map_err(map_export_error)?;
-    exporter.accept(record_log_debug).map_err(map_export_error)?;
-    exporter.accept(record_log_emerg).map_err(map_export_error)?;
-    exporter.accept(record_log_alert).map_err(map_export_error)?;
-    exporter.accept(record_log_notice).map_err(map_export_error)?;
+    // `try_log*` returns `None` for levels the producer filters out.
+    for record in [
+        record_log_info_tagged,
+        record_log_debug,
+        record_log_emerg,
+        record_log_alert,
+        record_log_notice,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        exporter.accept(record).map_err(map_export_error)?;
+    }
 
     embedded_io::Write::flush(exporter.writer_mut())?;
     Ok(())
@@ -94,16 +94,21 @@ fn main() {
     let record_data_acc_instance = producer.data_instance::<Acc>(&a, 1).unwrap();
     let record_data_error = producer.data::<ErrorData>(&err_code).unwrap();
     let record_param_sys_logger = producer.parameter_i32("SYS_LOGGER", 1).unwrap();
-    let record_log_info = producer.log(LogLevel::Info, 43, "info log");
-    let record_log_info_tagged = producer.log_tagged(LogLevel::Info, 1, 43, "info log");
+    let record_log_info = producer.try_log(LogLevel::Info, 43, "info log");
+    let record_log_info_tagged = producer.try_log_tagged(LogLevel::Info, 1, 43, "info log");
 
     exporter.accept(record_data_gyro).unwrap();
     exporter.accept(record_data_acc).unwrap();
     exporter.accept(record_data_acc_instance).unwrap();
     exporter.accept(record_data_error).unwrap();
     exporter.accept(record_param_sys_logger).unwrap();
-    exporter.accept(record_log_info).unwrap();
-    exporter.accept(record_log_info_tagged).unwrap();
+    // `try_log*` returns `None` for levels the producer filters out.
+    for record in [record_log_info, record_log_info_tagged]
+        .into_iter()
+        .flatten()
+    {
+        exporter.accept(record).unwrap();
+    }
 
     println!("done")
 }
@@ -73,17 +73,21 @@ async fn async_main() {
         .await
         .unwrap();
 
-    let record_log_boot = producer.log(LogLevel::Info, 100, "producer boot");
+    let record_log_boot = producer.try_log(LogLevel::Info, 100, "producer boot");
     let record_data_gyro = producer.data::<Gyro>(&g).unwrap();
     let record_data_acc = producer.data::<Acc>(&a).unwrap();
     let record_param_sys_logger = producer.parameter_i32("SYS_LOGGER", 1).unwrap();
-    let record_log_warn_tagged = producer.log_tagged(LogLevel::Warning, 7, 102, "warn");
+    let record_log_warn_tagged = producer.try_log_tagged(LogLevel::Warning, 7, 102, "warn");
 
-    exporter.accept(record_log_boot).await.unwrap();
+    if let Some(record) = record_log_boot {
+        exporter.accept(record).await.unwrap();
+    }
     exporter.accept(record_data_gyro).await.unwrap();
     exporter.accept(record_data_acc).await.unwrap();
     exporter.accept(record_param_sys_logger).await.unwrap();
-    exporter.accept(record_log_warn_tagged).await.unwrap();
+    if let Some(record) = record_log_warn_tagged {
+        exporter.accept(record).await.unwrap();
+    }
 
     println!("done");
 }
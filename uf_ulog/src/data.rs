@@ -1,3 +1,5 @@
+use crate::NestedMeta;
+
 #[derive(Debug)]
 pub struct LoggedString {
     pub level: LogLevel,
@@ -6,6 +8,7 @@ pub struct LoggedString {
     pub msg: &'static str,
 }
 
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Debug, PartialEq, Copy, Clone)]
 #[repr(u8)]
 pub enum LogLevel {
@@ -19,6 +22,24 @@ pub enum LogLevel {
     Debug = b'7',
 }
 
+impl TryFrom<u8> for LogLevel {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            b'0' => Ok(Self::Emerg),
+            b'1' => Ok(Self::Alert),
+            b'2' => Ok(Self::Crit),
+            b'3' => Ok(Self::Err),
+            b'4' => Ok(Self::Warning),
+            b'5' => Ok(Self::Notice),
+            b'6' => Ok(Self::Info),
+            b'7' => Ok(Self::Debug),
+            other => Err(other),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Subscription {
     pub multi_id: u8,
@@ -30,11 +51,52 @@ pub trait ULogData {
     const FORMAT: &'static str;
     const NAME: &'static str;
     const WIRE_SIZE: usize;
+    const NESTED: &'static [NestedMeta] = &[];
 
     fn encode(&self, buf: &mut [u8]) -> Result<usize, EncodeError>;
     fn timestamp(&self) -> u64;
+
+    fn decode(_buf: &[u8]) -> Result<Self, DecodeError>
+    where
+        Self: Sized,
+    {
+        Err(DecodeError::Unsupported)
+    }
 }
 
+pub trait ULogNested {
+    const FORMAT: &'static str;
+    const NAME: &'static str;
+    const WIRE_SIZE: usize;
+    const NESTED: &'static [NestedMeta] = &[];
+    const META: NestedMeta = NestedMeta {
+        name: Self::NAME,
+        format: Self::FORMAT,
+        nested: Self::NESTED,
+    };
+
+    fn encode(&self, buf: &mut [u8]) -> Result<usize, EncodeError>;
+
+    fn decode(_buf: &[u8]) -> Result<Self, DecodeError>
+    where
+        Self: Sized,
+    {
+        Err(DecodeError::Unsupported)
+    }
+}
+
+pub const fn fixed_str<const N: usize>(value: &str) -> [u8; N] {
+    let bytes = value.as_bytes();
+    let mut out = [0u8; N];
+    let mut i = 0;
+    while i < N && i < bytes.len() {
+        out[i] = bytes[i];
+        i += 1;
+    }
+    out
+}
+
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EncodeError {
     BufferOverflow,
@@ -45,6 +107,14 @@ pub enum EncodeError {
     TopicConflict,
 }
 
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    BufferTooShort,
+    Unsupported,
+}
+
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TrySendError {
     Full,
@@ -57,11 +127,142 @@ pub enum ParameterValue {
     F32(f32),
 }
 
+impl ParameterValue {
+    pub fn decode<'a>(key: &'a str, value: &[u8]) -> Option<(&'a str, Self)> {
+        let (ty, name) = key.split_once(' ')?;
+        let raw: [u8; 4] = value.try_into().ok()?;
+        match ty {
+            "int32_t" => Some((name, Self::I32(i32::from_le_bytes(raw)))),
+            "float" => Some((name, Self::F32(f32::from_le_bytes(raw)))),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DefaultTypes(u8);
+
+impl DefaultTypes {
+    pub const SYSTEM: Self = Self(1 << 0);
+    pub const CONFIGURATION: Self = Self(1 << 1);
+
+    pub const fn from_bits(bits: u8) -> Self {
+        Self(bits)
+    }
+
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl core::ops::BitOr for DefaultTypes {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        self.union(rhs)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InfoValue<'a> {
+    Str(&'a str),
+    I32(i32),
+    U32(u32),
+    I64(i64),
+    U64(u64),
+    F32(f32),
+}
+
+impl<'a> InfoValue<'a> {
+    pub const fn type_name(&self) -> &'static str {
+        match self {
+            Self::Str(_) => "char",
+            Self::I32(_) => "int32_t",
+            Self::U32(_) => "uint32_t",
+            Self::I64(_) => "int64_t",
+            Self::U64(_) => "uint64_t",
+            Self::F32(_) => "float",
+        }
+    }
+
+    pub fn decode(key: &'a str, value: &'a [u8]) -> Option<(&'a str, Self)> {
+        let (ty, name) = key.split_once(' ')?;
+        let decoded = match ty {
+            "int32_t" => Self::I32(i32::from_le_bytes(value.try_into().ok()?)),
+            "uint32_t" => Self::U32(u32::from_le_bytes(value.try_into().ok()?)),
+            "int64_t" => Self::I64(i64::from_le_bytes(value.try_into().ok()?)),
+            "uint64_t" => Self::U64(u64::from_le_bytes(value.try_into().ok()?)),
+            "float" => Self::F32(f32::from_le_bytes(value.try_into().ok()?)),
+            _ if ty.starts_with("char[") => Self::Str(core::str::from_utf8(value).ok()?),
+            _ => return None,
+        };
+        Some((name, decoded))
+    }
+
+    pub(crate) fn write_key<const N: usize>(&self, name: &str) -> Option<heapless::String<N>> {
+        match self {
+            Self::Str(text) => info_key("char", Some(text.len()), name),
+            _ => info_key(self.type_name(), None, name),
+        }
+    }
+
+    pub(crate) fn value_bytes<'s>(&'s self, scratch: &'s mut [u8; 8]) -> &'s [u8]
+    where
+        'a: 's,
+    {
+        let len = match *self {
+            Self::Str(text) => return text.as_bytes(),
+            Self::I32(v) => copy_le(scratch, &v.to_le_bytes()),
+            Self::U32(v) => copy_le(scratch, &v.to_le_bytes()),
+            Self::I64(v) => copy_le(scratch, &v.to_le_bytes()),
+            Self::U64(v) => copy_le(scratch, &v.to_le_bytes()),
+            Self::F32(v) => copy_le(scratch, &v.to_le_bytes()),
+        };
+        &scratch[..len]
+    }
+}
+
+pub(crate) fn info_key<const N: usize>(
+    ty: &str,
+    array_len: Option<usize>,
+    name: &str,
+) -> Option<heapless::String<N>> {
+    use core::fmt::Write as _;
+
+    let mut key = heapless::String::<N>::new();
+    let written = match array_len {
+        Some(len) => write!(key, "{ty}[{len}] {name}"),
+        None => write!(key, "{ty} {name}"),
+    };
+    if written.is_err() || key.len() > usize::from(u8::MAX) {
+        return None;
+    }
+    Some(key)
+}
+
+fn copy_le(scratch: &mut [u8; 8], bytes: &[u8]) -> usize {
+    scratch[..bytes.len()].copy_from_slice(bytes);
+    bytes.len()
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RecordKind {
     LoggedString,
     Data,
     Parameter,
+    Info,
+    MultiInfo,
+    ParameterDefault,
+    Dropout,
+    RemoveSubscription,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -79,6 +280,24 @@ pub enum RecordMeta {
     Parameter {
         value: ParameterValue,
     },
+    Info {
+        key_len: u8,
+    },
+    MultiInfo {
+        key_len: u8,
+        is_continued: bool,
+    },
+    ParameterDefault {
+        value: ParameterValue,
+        default_types: DefaultTypes,
+    },
+    Dropout {
+        duration_ms: u16,
+    },
+    RemoveSubscription {
+        topic_index: u16,
+        instance: u8,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -121,11 +340,74 @@ impl<const RECORD_CAP: usize> Record<RECORD_CAP> {
         })
     }
 
+    pub fn new_parameter_default(
+        key: &[u8],
+        value: ParameterValue,
+        default_types: DefaultTypes,
+    ) -> Option<Self> {
+        if key.len() > usize::from(u8::MAX) {
+            return None;
+        }
+        let bytes = heapless::Vec::from_slice(key).ok()?;
+        Some(Self {
+            meta: RecordMeta::ParameterDefault {
+                value,
+                default_types,
+            },
+            bytes,
+        })
+    }
+
+    pub fn new_dropout(duration_ms: u16) -> Self {
+        Self {
+            meta: RecordMeta::Dropout { duration_ms },
+            bytes: heapless::Vec::new(),
+        }
+    }
+
+    pub fn new_remove_subscription(topic_index: u16, instance: u8) -> Self {
+        Self {
+            meta: RecordMeta::RemoveSubscription {
+                topic_index,
+                instance,
+            },
+            bytes: heapless::Vec::new(),
+        }
+    }
+
+    pub fn new_info(key: &[u8], value: &[u8]) -> Option<Self> {
+        let key_len = u8::try_from(key.len()).ok()?;
+        let mut bytes = heapless::Vec::from_slice(key).ok()?;
+        bytes.extend_from_slice(value).ok()?;
+        Some(Self {
+            meta: RecordMeta::Info { key_len },
+            bytes,
+        })
+    }
+
+    pub fn new_multi_info(key: &[u8], value: &[u8], is_continued: bool) -> Option<Self> {
+        let key_len = u8::try_from(key.len()).ok()?;
+        let mut bytes = heapless::Vec::from_slice(key).ok()?;
+        bytes.extend_from_slice(value).ok()?;
+        Some(Self {
+            meta: RecordMeta::MultiInfo {
+                key_len,
+                is_continued,
+            },
+            bytes,
+        })
+    }
+
     pub fn kind(&self) -> RecordKind {
         match self.meta {
             RecordMeta::LoggedString { .. } => RecordKind::LoggedString,
             RecordMeta::Data { .. } => RecordKind::Data,
             RecordMeta::Parameter { .. } => RecordKind::Parameter,
+            RecordMeta::Info { .. } => RecordKind::Info,
+            RecordMeta::MultiInfo { .. } => RecordKind::MultiInfo,
+            RecordMeta::ParameterDefault { .. } => RecordKind::ParameterDefault,
+            RecordMeta::Dropout { .. } => RecordKind::Dropout,
+            RecordMeta::RemoveSubscription { .. } => RecordKind::RemoveSubscription,
         }
     }
 
@@ -0,0 +1,78 @@
+use crate::level::static_level_enabled;
+use crate::{LogLevel, Record};
+
+// Collects the bytes of one defmt frame between `acquire` and `release` of
+// the global logger generated by `ulog_defmt_global_logger!`.
+pub struct DefmtFrame<const RECORD_CAP: usize> {
+    bytes: heapless::Vec<u8, RECORD_CAP>,
+    truncated: bool,
+}
+
+impl<const RECORD_CAP: usize> DefmtFrame<RECORD_CAP> {
+    pub const fn new() -> Self {
+        Self {
+            bytes: heapless::Vec::new(),
+            truncated: false,
+        }
+    }
+
+    pub fn start(&mut self) {
+        self.bytes.clear();
+        self.truncated = false;
+    }
+
+    pub fn write(&mut self, bytes: &[u8]) {
+        if self.bytes.extend_from_slice(bytes).is_err() {
+            self.truncated = true;
+        }
+    }
+
+    // defmt levels live in the interned format string rather than in the
+    // frame, so every frame is logged at `Info` under `tag`. A frame cut at
+    // `RECORD_CAP` cannot be decoded and is dropped.
+    pub fn finish(&mut self, tag: u16, ts: u64) -> Option<Record<RECORD_CAP>> {
+        if self.truncated || !static_level_enabled(LogLevel::Info) {
+            return None;
+        }
+        Some(Record::new_log(LogLevel::Info, Some(tag), ts, &self.bytes))
+    }
+}
+
+impl<const RECORD_CAP: usize> Default for DefmtFrame<RECORD_CAP> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RecordMeta;
+
+    #[test]
+    fn frame_becomes_tagged_log_record() {
+        let mut frame = DefmtFrame::<16>::new();
+        frame.start();
+        frame.write(&[1, 0]);
+        frame.write(&[0xFF, 2]);
+
+        match frame.finish(7, 42) {
+            Some(record) => {
+                assert_eq!(record.bytes(), &[1, 0, 0xFF, 2]);
+                assert_eq!(
+                    record.meta(),
+                    RecordMeta::LoggedString {
+                        level: LogLevel::Info,
+                        tag: Some(7),
+                        ts: 42
+                    }
+                );
+            }
+            None => assert!(!static_level_enabled(LogLevel::Info)),
+        }
+
+        frame.start();
+        frame.write(&[0; 17]);
+        assert!(frame.finish(7, 43).is_none());
+    }
+}
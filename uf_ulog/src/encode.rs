@@ -0,0 +1,332 @@
+use embedded_io::SliceWriteError;
+
+use crate::wire::{self, MessageType};
+use crate::{
+    EncodeError, ExportError, FlagBits, InfoValue, LogProfile, Record, RecordMeta, TopicOf,
+    ULogData, ULogRegistry,
+};
+
+struct SliceCursor<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl SliceCursor<'_> {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), ExportError<SliceWriteError>> {
+        let end = self
+            .len
+            .checked_add(bytes.len())
+            .filter(|end| *end <= self.buf.len())
+            .ok_or(ExportError::Write(SliceWriteError::Full))?;
+        self.buf[self.len..end].copy_from_slice(bytes);
+        self.len = end;
+        Ok(())
+    }
+
+    fn write_message(
+        &mut self,
+        message: wire::Message<'_>,
+    ) -> Result<(), ExportError<SliceWriteError>> {
+        // Check the whole message fits so a failed call leaves no partial message.
+        if self.buf.len() - self.len < message.len() {
+            return Err(ExportError::Write(SliceWriteError::Full));
+        }
+        for part in message.parts() {
+            self.write(part)?;
+        }
+        Ok(())
+    }
+
+    fn write_info(
+        &mut self,
+        name: &str,
+        value: InfoValue<'_>,
+    ) -> Result<(), ExportError<SliceWriteError>> {
+        let key = value
+            .write_key::<{ u8::MAX as usize }>(name)
+            .ok_or(ExportError::MessageTooLarge)?;
+        let mut scratch = [0u8; 8];
+        let raw = value.value_bytes(&mut scratch);
+        self.write_message(wire::Message::info(key.as_bytes(), raw)?)
+    }
+}
+
+// Writes the same definitions section as the exporters' `start`, including
+// the info messages and the `log_profile` entry.
+pub fn encode_startup<R: ULogRegistry>(
+    timestamp_micros: u64,
+    flag_bits: FlagBits,
+    info: &[(&str, InfoValue<'_>)],
+    profile: Option<LogProfile>,
+    buf: &mut [u8],
+) -> Result<usize, ExportError<SliceWriteError>> {
+    let mut cursor = SliceCursor { buf, len: 0 };
+    cursor.write(&wire::write_header(timestamp_micros))?;
+    let flag_payload = flag_bits.encode();
+    cursor.write_message(wire::Message::flag_bits(&flag_payload)?)?;
+    for meta in R::REGISTRY.nested_formats() {
+        cursor.write_message(wire::Message::format(meta.name, meta.format)?)?;
+    }
+    for meta in R::REGISTRY.entries {
+        cursor.write_message(wire::Message::format(meta.name, meta.format)?)?;
+    }
+    for &(name, value) in info {
+        cursor.write_info(name, value)?;
+    }
+    if let Some(profile) = profile {
+        cursor.write_info("log_profile", InfoValue::Str(profile.name()))?;
+    }
+    Ok(cursor.len)
+}
+
+pub fn encode_add_subscription<R: ULogRegistry, const MAX_MULTI_IDS: usize>(
+    topic_index: u16,
+    instance: u8,
+    buf: &mut [u8],
+) -> Result<usize, ExportError<SliceWriteError>> {
+    let (msg_id, meta) = data_stream::<R, MAX_MULTI_IDS>(topic_index, instance)?;
+    let mut cursor = SliceCursor { buf, len: 0 };
+    cursor.write_message(wire::Message::add_subscription(
+        instance, msg_id, meta.name,
+    )?)?;
+    Ok(cursor.len)
+}
+
+pub fn encode_data<R, T, const MAX_MULTI_IDS: usize>(
+    value: &T,
+    instance: u8,
+    buf: &mut [u8],
+) -> Result<usize, ExportError<SliceWriteError>>
+where
+    R: ULogRegistry,
+    T: ULogData + TopicOf<R>,
+{
+    let (msg_id, meta) = data_stream::<R, MAX_MULTI_IDS>(<T as TopicOf<R>>::TOPIC.id(), instance)?;
+    if T::WIRE_SIZE != meta.wire_size {
+        return Err(ExportError::InvalidWireSize);
+    }
+    let payload_len = wire::data_payload_len::<SliceWriteError>(T::WIRE_SIZE)?;
+    let header = wire::message_header(payload_len, MessageType::Data)?;
+    let prefix = wire::data_prefix(msg_id);
+    let payload_start = header.len() + prefix.len();
+    let total_len = payload_start + T::WIRE_SIZE;
+    let Some(out) = buf.get_mut(..total_len) else {
+        return Err(ExportError::Write(SliceWriteError::Full));
+    };
+
+    // Encode straight into the caller's buffer behind the 'D' header.
+    let encoded_len = value
+        .encode(&mut out[payload_start..])
+        .map_err(|err| match err {
+            EncodeError::BufferOverflow => ExportError::Write(SliceWriteError::Full),
+            _ => ExportError::InvalidWireSize,
+        })?;
+    if encoded_len != T::WIRE_SIZE {
+        return Err(ExportError::InvalidWireSize);
+    }
+    out[..header.len()].copy_from_slice(&header);
+    out[header.len()..payload_start].copy_from_slice(&prefix);
+    Ok(total_len)
+}
+
+pub fn encode_record<R: ULogRegistry, const RECORD_CAP: usize, const MAX_MULTI_IDS: usize>(
+    record: &Record<RECORD_CAP>,
+    buf: &mut [u8],
+) -> Result<usize, ExportError<SliceWriteError>> {
+    let meta = record.meta();
+    let bytes = record.bytes();
+    let msg_id = match meta {
+        RecordMeta::Data {
+            topic_index,
+            instance,
+            ..
+        } => {
+            let (msg_id, meta) = data_stream::<R, MAX_MULTI_IDS>(topic_index, instance)?;
+            if bytes.len() != meta.wire_size {
+                return Err(ExportError::InvalidWireSize);
+            }
+            msg_id
+        }
+        RecordMeta::RemoveSubscription {
+            topic_index,
+            instance,
+        } => data_stream::<R, MAX_MULTI_IDS>(topic_index, instance)?.0,
+        _ => 0,
+    };
+    let mut cursor = SliceCursor { buf, len: 0 };
+    cursor.write_message(wire::Message::record(meta, bytes, msg_id)?)?;
+    Ok(cursor.len)
+}
+
+fn data_stream<R: ULogRegistry, const MAX_MULTI_IDS: usize>(
+    topic_index: u16,
+    instance: u8,
+) -> Result<(u16, &'static crate::MessageMeta), ExportError<SliceWriteError>> {
+    if usize::from(instance) >= MAX_MULTI_IDS {
+        return Err(ExportError::InvalidMultiId);
+    }
+    let meta = wire::registry_entry::<R, SliceWriteError>(usize::from(topic_index))?;
+    let slot = wire::stream_slot::<MAX_MULTI_IDS>(usize::from(topic_index), usize::from(instance))
+        .ok_or(ExportError::TooManyStreams)?;
+    Ok((wire::slot_msg_id(slot)?, meta))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exporter::FormatsPending;
+    use crate::{LogLevel, ParameterValue, ULogCoreExporter};
+
+    const CAP: usize = 32;
+    const MI: usize = 8;
+
+    #[derive(Default)]
+    struct VecSink {
+        bytes: std::vec::Vec<u8>,
+    }
+
+    impl embedded_io::ErrorType for VecSink {
+        type Error = core::convert::Infallible;
+    }
+
+    impl embedded_io::Write for VecSink {
+        fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            self.bytes.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    enum TestMessages {}
+
+    impl crate::ULogRegistry for TestMessages {
+        const REGISTRY: crate::Registry = crate::Registry::new(&[crate::MessageMeta {
+            name: "sample",
+            format: "uint64_t timestamp;",
+            wire_size: 8,
+        }]);
+    }
+
+    #[test]
+    fn slice_encoding_matches_exporter_output() {
+        let data = Record::<CAP>::new_data(0, 1, 0, &[1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+        let log = Record::<CAP>::new_log(LogLevel::Info, Some(2), 1, b"hello");
+        let param = Record::<CAP>::new_parameter(b"int32_t P", ParameterValue::I32(3)).unwrap();
+        let (key, value) = (b"char[2] sys_name", b"uf");
+        let info = Record::<CAP>::new_info(key, value).unwrap();
+
+        const INFO: &[(&str, InfoValue<'static>)] = &[("ver_sw", InfoValue::Str("abc"))];
+        let profile = LogProfile::all("full");
+
+        let mut exporter = ULogCoreExporter::<_, TestMessages, FormatsPending, CAP, MI, 64>::new(
+            VecSink::default(),
+        )
+        .with_info(INFO)
+        .with_profile(profile)
+        .start(100)
+        .unwrap();
+        exporter.accept(data.clone()).unwrap();
+        exporter.accept(log.clone()).unwrap();
+        exporter.accept(param.clone()).unwrap();
+        exporter
+            .emit_info("sys_name", InfoValue::Str("uf"))
+            .unwrap();
+
+        let mut buf = [0u8; 512];
+        let mut len =
+            encode_startup::<TestMessages>(100, FlagBits::new(), INFO, Some(profile), &mut buf)
+                .unwrap();
+        len += encode_add_subscription::<TestMessages, MI>(0, 1, &mut buf[len..]).unwrap();
+        for record in [&data, &log, &param, &info] {
+            len += encode_record::<TestMessages, CAP, MI>(record, &mut buf[len..]).unwrap();
+        }
+
+        assert_eq!(&buf[..len], exporter.writer_mut().bytes.as_slice());
+    }
+
+    struct Sample(u64);
+
+    impl ULogData for Sample {
+        const FORMAT: &'static str = "uint64_t timestamp;";
+        const NAME: &'static str = "sample";
+        const WIRE_SIZE: usize = 8;
+
+        fn encode(&self, buf: &mut [u8]) -> Result<usize, crate::EncodeError> {
+            buf[..8].copy_from_slice(&self.0.to_le_bytes());
+            Ok(8)
+        }
+
+        fn timestamp(&self) -> u64 {
+            self.0
+        }
+    }
+
+    impl TopicOf<TestMessages> for Sample {
+        const TOPIC: crate::Topic<Self> = crate::Topic::new(0);
+    }
+
+    #[test]
+    fn encode_data_matches_record_encoding() {
+        let value = Sample(0x0807_0605_0403_0201);
+        let record = Record::<CAP>::new_data(0, 1, value.0, &value.0.to_le_bytes()).unwrap();
+
+        let mut expected = [0u8; 32];
+        let expected_len = encode_record::<TestMessages, CAP, MI>(&record, &mut expected).unwrap();
+        let mut buf = [0u8; 32];
+        let len = encode_data::<TestMessages, _, MI>(&value, 1, &mut buf).unwrap();
+
+        assert_eq!(&buf[..len], &expected[..expected_len]);
+        assert_eq!(
+            encode_data::<TestMessages, _, MI>(&value, 1, &mut buf[..len - 1]),
+            Err(ExportError::Write(SliceWriteError::Full))
+        );
+    }
+
+    struct Overflowing;
+
+    impl ULogData for Overflowing {
+        const FORMAT: &'static str = "uint64_t timestamp;";
+        const NAME: &'static str = "sample";
+        const WIRE_SIZE: usize = 8;
+
+        fn encode(&self, _buf: &mut [u8]) -> Result<usize, crate::EncodeError> {
+            Err(crate::EncodeError::BufferOverflow)
+        }
+
+        fn timestamp(&self) -> u64 {
+            0
+        }
+    }
+
+    impl TopicOf<TestMessages> for Overflowing {
+        const TOPIC: crate::Topic<Self> = crate::Topic::new(0);
+    }
+
+    #[test]
+    fn encode_data_reports_buffer_overflow() {
+        let mut buf = [0u8; 32];
+        assert_eq!(
+            encode_data::<TestMessages, _, MI>(&Overflowing, 1, &mut buf),
+            Err(ExportError::Write(SliceWriteError::Full))
+        );
+    }
+
+    #[test]
+    fn short_buffer_is_rejected() {
+        let log = Record::<CAP>::new_log(LogLevel::Info, None, 1, b"hello");
+        let mut buf = [0xAAu8; 16];
+
+        assert_eq!(
+            encode_record::<TestMessages, CAP, MI>(&log, &mut buf),
+            Err(ExportError::Write(SliceWriteError::Full))
+        );
+        assert_eq!(buf, [0xAA; 16]);
+        assert_eq!(
+            encode_add_subscription::<TestMessages, MI>(1, 0, &mut buf),
+            Err(ExportError::InvalidTopicIndex)
+        );
+    }
+}
@@ -1,11 +1,32 @@
 use core::marker::PhantomData;
 
-use crate::wire::{self, MessageType};
-use crate::{ExportError, ParameterValue, Record, RecordMeta, ULogRegistry};
+use crate::wire::{self, FlagBits};
+use crate::{
+    Clock, ExportError, ExportStats, ExportStatsData, InfoValue, LevelFilter, LogLevel, LogProfile,
+    Record, RecordMeta, RecordReceiver, RingReader, StreamStatsData, SyncPolicy, TopicOf, ULogData,
+    ULogRegistry,
+};
 
 pub struct FormatsPending;
 pub struct StreamingReady;
 
+// Streaming state the poll exporter restores when a message does not fit its
+// pending buffer, so a rejected record leaves no trace.
+#[derive(Clone, Copy)]
+pub(crate) struct CoreState<const MAX_STREAMS: usize> {
+    subscribed: [u8; MAX_STREAMS],
+    dropped_streams: u32,
+    queue_overruns: u32,
+    rejected_records: u32,
+    last_data_ts: Option<u64>,
+    pending_dropout_micros: Option<u64>,
+    overrun_gap: bool,
+    bytes_since_sync: u64,
+    last_sync_ts: Option<u64>,
+    stats: ExportStats<MAX_STREAMS>,
+    profile: Option<LogProfile>,
+}
+
 pub struct ULogCoreExporter<
     W,
     R: ULogRegistry,
@@ -17,6 +38,19 @@ pub struct ULogCoreExporter<
     writer: W,
     subscribed: [u8; MAX_STREAMS],
     dropped_streams: u32,
+    queue_overruns: u32,
+    rejected_records: u32,
+    last_data_ts: Option<u64>,
+    pending_dropout_micros: Option<u64>,
+    overrun_gap: bool,
+    flag_bits: FlagBits,
+    sync_policy: SyncPolicy,
+    bytes_since_sync: u64,
+    last_sync_ts: Option<u64>,
+    stats: ExportStats<MAX_STREAMS>,
+    profile: Option<LogProfile>,
+    level_filter: LevelFilter,
+    info: &'static [(&'static str, InfoValue<'static>)],
     _messages: PhantomData<R>,
     _state: PhantomData<State>,
 }
@@ -32,11 +66,53 @@ where
             writer,
             subscribed: [0; MAX_STREAMS],
             dropped_streams: 0,
+            queue_overruns: 0,
+            rejected_records: 0,
+            last_data_ts: None,
+            pending_dropout_micros: None,
+            overrun_gap: false,
+            flag_bits: FlagBits::new(),
+            sync_policy: SyncPolicy::Manual,
+            bytes_since_sync: 0,
+            last_sync_ts: None,
+            stats: ExportStats::new(),
+            profile: None,
+            level_filter: LevelFilter::new(LogLevel::Debug),
+            info: &[],
             _messages: PhantomData,
             _state: PhantomData,
         }
     }
 
+    pub fn with_flag_bits(mut self, flag_bits: FlagBits) -> Self {
+        self.flag_bits = flag_bits;
+        self
+    }
+
+    pub fn with_sync_policy(mut self, sync_policy: SyncPolicy) -> Self {
+        self.sync_policy = sync_policy;
+        self
+    }
+
+    pub fn with_profile(mut self, profile: LogProfile) -> Self {
+        self.profile = Some(profile);
+        self
+    }
+
+    pub fn with_level_filter(mut self, level_filter: LevelFilter) -> Self {
+        self.level_filter = level_filter;
+        self
+    }
+
+    // Written as info messages after the formats, e.g. `sys_name` or `ver_sw`.
+    // Values only known at runtime (a serial number read from flash) go
+    // through `emit_info` right after `start`: nothing but definitions has
+    // been written until the first record, so they land in the same section.
+    pub fn with_info(mut self, info: &'static [(&'static str, InfoValue<'static>)]) -> Self {
+        self.info = info;
+        self
+    }
+
     pub fn start(
         mut self,
         timestamp_micros: u64,
@@ -45,7 +121,17 @@ where
         ExportError<<W as embedded_io::ErrorType>::Error>,
     > {
         self.emit_startup(timestamp_micros)?;
-        Ok(self.into_streaming())
+        Ok(self.into_state())
+    }
+
+    pub fn start_with_clock<C: Clock>(
+        self,
+        clock: &C,
+    ) -> Result<
+        ULogCoreExporter<W, R, StreamingReady, RECORD_CAP, MAX_MULTI_IDS, MAX_STREAMS>,
+        ExportError<<W as embedded_io::ErrorType>::Error>,
+    > {
+        self.start(clock.now_micros())
     }
 
     fn emit_startup(
@@ -54,24 +140,21 @@ where
     ) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>> {
         self.write_header(timestamp_micros)?;
         self.write_flag_bits()?;
+        for meta in R::REGISTRY.nested_formats() {
+            self.write_format(meta.name, meta.format)?;
+        }
         for meta in R::REGISTRY.entries {
             self.write_format(meta.name, meta.format)?;
         }
+        for &(name, value) in self.info {
+            self.write_info_value(name, value)?;
+        }
+        if let Some(profile) = self.profile {
+            self.write_profile_info(profile)?;
+        }
 
         Ok(())
     }
-
-    fn into_streaming(
-        self,
-    ) -> ULogCoreExporter<W, R, StreamingReady, RECORD_CAP, MAX_MULTI_IDS, MAX_STREAMS> {
-        ULogCoreExporter {
-            writer: self.writer,
-            subscribed: self.subscribed,
-            dropped_streams: self.dropped_streams,
-            _messages: PhantomData,
-            _state: PhantomData,
-        }
-    }
 }
 
 impl<
@@ -90,26 +173,165 @@ where
         &mut self.writer
     }
 
+    pub(crate) fn writer(&self) -> &W {
+        &self.writer
+    }
+
     pub fn dropped_streams(&self) -> u32 {
         self.dropped_streams
     }
 
+    pub fn queue_overruns(&self) -> u32 {
+        self.queue_overruns
+    }
+
+    // Records `drain` and `drain_ring` dropped because they failed validation.
+    pub fn rejected_records(&self) -> u32 {
+        self.rejected_records
+    }
+
+    pub fn stats(&self) -> &ExportStats<MAX_STREAMS> {
+        &self.stats
+    }
+
+    pub fn profile(&self) -> Option<LogProfile> {
+        self.profile
+    }
+
+    pub fn level_filter(&self) -> LevelFilter {
+        self.level_filter
+    }
+
+    pub fn set_level_filter(&mut self, level_filter: LevelFilter) {
+        self.level_filter = level_filter;
+    }
+
+    pub fn flag_bits(&self) -> FlagBits {
+        self.flag_bits
+    }
+
+    pub(crate) fn into_state<S>(
+        self,
+    ) -> ULogCoreExporter<W, R, S, RECORD_CAP, MAX_MULTI_IDS, MAX_STREAMS> {
+        ULogCoreExporter {
+            writer: self.writer,
+            subscribed: self.subscribed,
+            dropped_streams: self.dropped_streams,
+            queue_overruns: self.queue_overruns,
+            rejected_records: self.rejected_records,
+            last_data_ts: self.last_data_ts,
+            pending_dropout_micros: self.pending_dropout_micros,
+            overrun_gap: self.overrun_gap,
+            flag_bits: self.flag_bits,
+            sync_policy: self.sync_policy,
+            bytes_since_sync: self.bytes_since_sync,
+            last_sync_ts: self.last_sync_ts,
+            stats: self.stats,
+            profile: self.profile,
+            level_filter: self.level_filter,
+            info: self.info,
+            _messages: PhantomData,
+            _state: PhantomData,
+        }
+    }
+
+    pub(crate) fn info(&self) -> &'static [(&'static str, InfoValue<'static>)] {
+        self.info
+    }
+
+    pub(crate) fn snapshot(&self) -> CoreState<MAX_STREAMS> {
+        CoreState {
+            subscribed: self.subscribed,
+            dropped_streams: self.dropped_streams,
+            queue_overruns: self.queue_overruns,
+            rejected_records: self.rejected_records,
+            last_data_ts: self.last_data_ts,
+            pending_dropout_micros: self.pending_dropout_micros,
+            overrun_gap: self.overrun_gap,
+            bytes_since_sync: self.bytes_since_sync,
+            last_sync_ts: self.last_sync_ts,
+            stats: self.stats,
+            profile: self.profile,
+        }
+    }
+
+    pub(crate) fn restore(&mut self, state: CoreState<MAX_STREAMS>) {
+        self.subscribed = state.subscribed;
+        self.dropped_streams = state.dropped_streams;
+        self.queue_overruns = state.queue_overruns;
+        self.rejected_records = state.rejected_records;
+        self.last_data_ts = state.last_data_ts;
+        self.pending_dropout_micros = state.pending_dropout_micros;
+        self.overrun_gap = state.overrun_gap;
+        self.bytes_since_sync = state.bytes_since_sync;
+        self.last_sync_ts = state.last_sync_ts;
+        self.stats = state.stats;
+        self.profile = state.profile;
+    }
+
     fn write_record_inner(
         &mut self,
         record: Record<RECORD_CAP>,
     ) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>> {
-        match record.meta() {
+        self.write_record_parts(record.meta(), record.bytes())
+    }
+
+    pub(crate) fn write_record_parts(
+        &mut self,
+        meta: RecordMeta,
+        bytes: &[u8],
+    ) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>> {
+        self.write_record_body(meta, bytes)?;
+        self.sync_if_due(meta)
+    }
+
+    fn sync_if_due(
+        &mut self,
+        meta: RecordMeta,
+    ) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>> {
+        let due = match self.sync_policy {
+            SyncPolicy::Manual => false,
+            SyncPolicy::EveryBytes(period) => self.bytes_since_sync >= u64::from(period),
+            SyncPolicy::EveryMicros(period) => {
+                let ts = match meta {
+                    RecordMeta::Data { ts, .. } | RecordMeta::LoggedString { ts, .. } => ts,
+                    _ => return Ok(()),
+                };
+                let last = *self.last_sync_ts.get_or_insert(ts);
+                let due = ts.saturating_sub(last) >= period;
+                if due {
+                    self.last_sync_ts = Some(ts);
+                }
+                due
+            }
+        };
+        if due {
+            self.write_sync()?;
+        }
+        Ok(())
+    }
+
+    fn write_record_body(
+        &mut self,
+        meta: RecordMeta,
+        bytes: &[u8],
+    ) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>> {
+        if let RecordMeta::Data { ts, .. } | RecordMeta::LoggedString { ts, .. } = meta {
+            self.close_overrun_gap(ts);
+        }
+        match meta {
             RecordMeta::LoggedString { level, tag, ts } => {
-                if let Some(tag) = tag {
-                    self.write_tagged_log(level as u8, tag, ts, record.bytes())
-                } else {
-                    self.write_log(level as u8, ts, record.bytes())
+                if !self.level_filter.allows(level, tag) {
+                    return Ok(());
                 }
+                self.write_message(wire::Message::log(level as u8, tag, ts, bytes)?)?;
+                self.stats.record_log(level);
+                Ok(())
             }
             RecordMeta::Data {
                 topic_index,
                 instance,
-                ..
+                ts,
             } => {
                 let topic_index_usize = usize::from(topic_index);
                 if usize::from(instance) >= MAX_MULTI_IDS {
@@ -119,152 +341,189 @@ where
                 let meta = wire::registry_entry::<R, <W as embedded_io::ErrorType>::Error>(
                     topic_index_usize,
                 )?;
-                if record.bytes().len() != meta.wire_size {
+                if bytes.len() != meta.wire_size {
                     return Err(ExportError::InvalidWireSize);
                 }
+                if self
+                    .profile
+                    .is_some_and(|profile| !profile.is_enabled(topic_index))
+                {
+                    return Ok(());
+                }
 
                 let Some(slot) =
                     wire::stream_slot::<MAX_MULTI_IDS>(topic_index_usize, usize::from(instance))
                 else {
-                    self.dropped_streams = self.dropped_streams.saturating_add(1);
-                    return Ok(());
+                    return self.discard_data();
                 };
 
                 let msg_id = wire::slot_msg_id::<<W as embedded_io::ErrorType>::Error>(slot)?;
 
                 let Some(subscribed) = self.subscribed.get(slot).copied() else {
-                    self.dropped_streams = self.dropped_streams.saturating_add(1);
-                    return Ok(());
+                    return self.discard_data();
                 };
 
                 if subscribed == 0 {
-                    self.write_add_subscription(instance, msg_id, meta.name)?;
+                    self.write_message(wire::Message::add_subscription(
+                        instance, msg_id, meta.name,
+                    )?)?;
                     let Some(subscribed) = self.subscribed.get_mut(slot) else {
-                        self.dropped_streams = self.dropped_streams.saturating_add(1);
-                        return Ok(());
+                        return self.discard_data();
                     };
                     *subscribed = 1;
                 }
 
-                self.write_data(msg_id, record.bytes())
+                let message = wire::Message::data(msg_id, bytes)?;
+                let message_len = message.len();
+                self.write_message(message)?;
+                self.stats.record_stream(slot, message_len);
+                self.last_data_ts = Some(self.last_data_ts.map_or(ts, |last| last.max(ts)));
+                Ok(())
+            }
+            RecordMeta::Dropout { duration_ms } => self.write_dropout(duration_ms),
+            RecordMeta::RemoveSubscription {
+                topic_index,
+                instance,
+            } => self.remove_subscription(topic_index, instance),
+            // These records belong to no stream, so no message id is needed.
+            RecordMeta::Parameter { .. }
+            | RecordMeta::ParameterDefault { .. }
+            | RecordMeta::Info { .. }
+            | RecordMeta::MultiInfo { .. } => {
+                self.write_message(wire::Message::record(meta, bytes, 0)?)
             }
-            RecordMeta::Parameter { value } => self.write_parameter(record.bytes(), value),
         }
     }
 
-    fn write_header(
+    pub(crate) fn write_profile_info(
         &mut self,
-        timestamp_micros: u64,
+        profile: LogProfile,
     ) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>> {
-        let header = wire::write_header(timestamp_micros);
-        self.write_all(&header)
-    }
-
-    fn write_flag_bits(&mut self) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>> {
-        let payload = [0u8; 40];
-        self.write_message(MessageType::FlagBits, &payload)
+        self.write_info_value("log_profile", InfoValue::Str(profile.name()))
     }
 
-    fn write_sync(&mut self) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>> {
-        self.write_message(MessageType::Sync, &wire::ULOG_SYNC_MAGIC)
-    }
-
-    fn write_format(
+    pub(crate) fn write_info_value(
         &mut self,
         name: &str,
-        format: &str,
+        value: InfoValue<'_>,
     ) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>> {
-        let _ = wire::format_payload_len::<<W as embedded_io::ErrorType>::Error>(name, format)?;
-        let separator = [b':'];
-        let parts = [name.as_bytes(), &separator, format.as_bytes()];
-        self.write_message_parts(MessageType::Format, &parts)
+        let key = value
+            .write_key::<{ u8::MAX as usize }>(name)
+            .ok_or(ExportError::MessageTooLarge)?;
+        let mut scratch = [0u8; 8];
+        let raw = value.value_bytes(&mut scratch);
+        self.write_message(wire::Message::info(key.as_bytes(), raw)?)
     }
 
-    fn write_add_subscription(
+    fn remove_subscription(
         &mut self,
-        multi_id: u8,
-        msg_id: u16,
-        name: &str,
+        topic_index: u16,
+        instance: u8,
     ) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>> {
-        let _ = wire::add_subscription_payload_len::<<W as embedded_io::ErrorType>::Error>(name)?;
-        let prefix = wire::add_subscription_prefix(multi_id, msg_id);
-        let parts = [&prefix[..], name.as_bytes()];
-        self.write_message_parts(MessageType::AddSubscription, &parts)
+        let topic_index_usize = usize::from(topic_index);
+        if usize::from(instance) >= MAX_MULTI_IDS {
+            return Err(ExportError::InvalidMultiId);
+        }
+        let _ = wire::registry_entry::<R, <W as embedded_io::ErrorType>::Error>(topic_index_usize)?;
+
+        let Some(slot) =
+            wire::stream_slot::<MAX_MULTI_IDS>(topic_index_usize, usize::from(instance))
+        else {
+            return Ok(());
+        };
+        let Some(subscribed) = self.subscribed.get_mut(slot) else {
+            return Ok(());
+        };
+        if *subscribed == 0 {
+            return Ok(());
+        }
+
+        *subscribed = 0;
+        let msg_id = wire::slot_msg_id::<<W as embedded_io::ErrorType>::Error>(slot)?;
+        self.write_message(wire::Message::remove_subscription(msg_id)?)
+    }
+
+    // Streams beyond `MAX_STREAMS` are a configuration error rather than lost
+    // time, so their records are counted in `dropped_streams` without a dropout.
+    fn discard_data(&mut self) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>> {
+        self.dropped_streams = self.dropped_streams.saturating_add(1);
+        Ok(())
+    }
+
+    // Overrun records were never seen, so their duration is estimated as the
+    // time from the last written data to the next timestamped record.
+    fn close_overrun_gap(&mut self, ts: u64) {
+        if !core::mem::take(&mut self.overrun_gap) {
+            return;
+        }
+        let gap = self.last_data_ts.map_or(0, |last| ts.saturating_sub(last));
+        self.add_dropout(gap);
+    }
+
+    fn add_dropout(&mut self, micros: u64) {
+        let pending = self.pending_dropout_micros.unwrap_or(0);
+        self.pending_dropout_micros = Some(pending.saturating_add(micros));
+    }
+
+    fn flush_dropout(&mut self) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>> {
+        self.overrun_gap = false;
+        let Some(micros) = self.pending_dropout_micros.take() else {
+            return Ok(());
+        };
+        let message = wire::Message::dropout(wire::dropout_duration_ms(0, micros))?;
+        self.write_parts(&message)
     }
 
-    fn write_data(
+    pub(crate) fn write_header(
         &mut self,
-        msg_id: u16,
-        data: &[u8],
+        timestamp_micros: u64,
     ) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>> {
-        let _ = wire::data_payload_len::<<W as embedded_io::ErrorType>::Error>(data.len())?;
-        let prefix = wire::data_prefix(msg_id);
-        let parts = [&prefix[..], data];
-        self.write_message_parts(MessageType::Data, &parts)
+        let header = wire::write_header(timestamp_micros);
+        self.write_all(&header)
     }
 
-    fn write_log(
+    pub(crate) fn write_flag_bits(
         &mut self,
-        level: u8,
-        timestamp: u64,
-        text: &[u8],
     ) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>> {
-        let _ = wire::log_payload_len::<<W as embedded_io::ErrorType>::Error>(text.len())?;
-        let prefix = wire::log_prefix(level, timestamp);
-        let parts = [&prefix[..], text];
-        self.write_message_parts(MessageType::LoggedString, &parts)
+        let payload = self.flag_bits.encode();
+        self.write_message(wire::Message::flag_bits(&payload)?)
     }
 
-    fn write_tagged_log(
+    fn write_sync(&mut self) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>> {
+        self.write_message(wire::Message::sync()?)?;
+        self.bytes_since_sync = 0;
+        Ok(())
+    }
+
+    fn write_dropout(
         &mut self,
-        level: u8,
-        tag: u16,
-        timestamp: u64,
-        text: &[u8],
+        duration_ms: u16,
     ) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>> {
-        let _ = wire::tagged_log_payload_len::<<W as embedded_io::ErrorType>::Error>(text.len())?;
-        let prefix = wire::tagged_log_prefix(level, tag, timestamp);
-        let parts = [&prefix[..], text];
-        self.write_message_parts(MessageType::TaggedLoggedString, &parts)
+        self.add_dropout(u64::from(duration_ms) * 1000);
+        self.flush_dropout()
     }
 
-    fn write_parameter(
+    pub(crate) fn write_format(
         &mut self,
-        key: &[u8],
-        value: ParameterValue,
+        name: &str,
+        format: &str,
     ) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>> {
-        let raw = match value {
-            ParameterValue::I32(v) => v.to_le_bytes(),
-            ParameterValue::F32(v) => v.to_le_bytes(),
-        };
-        let _ = wire::parameter_payload_len::<<W as embedded_io::ErrorType>::Error>(key, &raw)?;
-        let key_len = wire::parameter_prefix::<<W as embedded_io::ErrorType>::Error>(key)?;
-        let parts = [&key_len[..], key, &raw];
-        self.write_message_parts(MessageType::Parameter, &parts)
+        self.write_message(wire::Message::format(name, format)?)
     }
 
     fn write_message(
         &mut self,
-        msg_type: MessageType,
-        payload: &[u8],
+        message: wire::Message<'_>,
     ) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>> {
-        let header = wire::message_header(payload.len(), msg_type)?;
-        self.write_all(&header)?;
-        self.write_all(payload)
+        self.flush_dropout()?;
+        self.write_parts(&message)
     }
 
-    fn write_message_parts(
+    fn write_parts(
         &mut self,
-        msg_type: MessageType,
-        parts: &[&[u8]],
+        message: &wire::Message<'_>,
     ) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>> {
-        let mut payload_len = 0usize;
-        for part in parts {
-            payload_len = wire::checked_total_len(payload_len, part.len(), usize::MAX)?;
-        }
-        let header = wire::message_header(payload_len, msg_type)?;
-        self.write_all(&header)?;
-        for part in parts {
+        for part in message.parts() {
             self.write_all(part)?;
         }
         Ok(())
@@ -274,7 +533,10 @@ where
         &mut self,
         bytes: &[u8],
     ) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>> {
-        self.writer.write_all(bytes).map_err(ExportError::Write)
+        self.writer.write_all(bytes).map_err(ExportError::Write)?;
+        self.stats.record_bytes(bytes.len());
+        self.bytes_since_sync = self.bytes_since_sync.saturating_add(bytes.len() as u64);
+        Ok(())
     }
 }
 
@@ -291,15 +553,242 @@ where
         self.write_record_inner(record)
     }
 
+    pub fn drain(
+        &mut self,
+        receiver: &mut RecordReceiver<'_, RECORD_CAP>,
+    ) -> Result<usize, ExportError<<W as embedded_io::ErrorType>::Error>> {
+        let overruns = receiver.take_overruns();
+        self.drain_with(overruns, |exporter| {
+            let record = receiver.try_recv()?;
+            Some(exporter.write_record_inner(record))
+        })
+    }
+
+    pub fn drain_ring<const N: usize>(
+        &mut self,
+        reader: &mut RingReader<'_, N>,
+    ) -> Result<usize, ExportError<<W as embedded_io::ErrorType>::Error>> {
+        let overruns = reader.take_overruns();
+        self.drain_with(overruns, |exporter| {
+            let (meta, bytes) = reader.peek()?;
+            let result = exporter.write_record_parts(meta, bytes);
+            // Consumed either way: a rejected frame would block the ring and a
+            // partly written one must not be written again.
+            reader.consume();
+            Some(result)
+        })
+    }
+
+    // Records lost to an overrun were sent after everything still queued, so
+    // their dropout follows the drained records, written before the next
+    // message once its duration is known. Invalid records are counted and
+    // skipped; after a `Write` error the log may end in a partial message and
+    // must be closed.
+    pub(crate) fn drain_with<F>(
+        &mut self,
+        overruns: u32,
+        mut write_next: F,
+    ) -> Result<usize, ExportError<<W as embedded_io::ErrorType>::Error>>
+    where
+        F: FnMut(
+            &mut Self,
+        ) -> Option<Result<(), ExportError<<W as embedded_io::ErrorType>::Error>>>,
+    {
+        self.queue_overruns = self.queue_overruns.saturating_add(overruns);
+        let mut drained = 0;
+        let mut result = Ok(());
+        while let Some(next) = write_next(self) {
+            match next {
+                Ok(()) => drained += 1,
+                Err(ExportError::Write(err)) => {
+                    result = Err(ExportError::Write(err));
+                    break;
+                }
+                Err(_) => self.rejected_records = self.rejected_records.saturating_add(1),
+            }
+        }
+        if overruns > 0 {
+            self.add_dropout(0);
+            self.overrun_gap = true;
+        }
+        result.map(|()| drained)
+    }
+
+    // Writes an `ExportStatsData` summary followed by one `StreamStatsData`
+    // per active stream slot, typically right before closing the log.
+    pub fn emit_stats(
+        &mut self,
+        timestamp: u64,
+    ) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>>
+    where
+        ExportStatsData: TopicOf<R>,
+        StreamStatsData: TopicOf<R>,
+    {
+        // Report the counters as they were before the stats rows themselves.
+        let stats = self.stats;
+        let summary = ExportStatsData {
+            timestamp,
+            total_bytes: stats.total_bytes,
+            dropped_streams: self.dropped_streams,
+            queue_overruns: self.queue_overruns,
+            logs_by_level: stats.logs_by_level,
+        };
+        let mut buf = [0u8; ExportStatsData::WIRE_SIZE];
+        let len = summary
+            .encode(&mut buf)
+            .map_err(|_| ExportError::InvalidWireSize)?;
+        let topic_index = <ExportStatsData as TopicOf<R>>::TOPIC.id();
+        self.write_stats_row(topic_index, timestamp, &buf[..len])?;
+        for (slot, stream) in stats.streams.iter().enumerate() {
+            if stream.messages == 0 {
+                continue;
+            }
+            let Ok(slot) = u16::try_from(slot) else {
+                break;
+            };
+            let value = StreamStatsData {
+                timestamp,
+                bytes: stream.bytes,
+                messages: stream.messages,
+                slot,
+            };
+            let mut buf = [0u8; StreamStatsData::WIRE_SIZE];
+            let len = value
+                .encode(&mut buf)
+                .map_err(|_| ExportError::InvalidWireSize)?;
+            let topic_index = <StreamStatsData as TopicOf<R>>::TOPIC.id();
+            self.write_stats_row(topic_index, timestamp, &buf[..len])?;
+        }
+        Ok(())
+    }
+
+    fn write_stats_row(
+        &mut self,
+        topic_index: u16,
+        ts: u64,
+        bytes: &[u8],
+    ) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>> {
+        let meta = RecordMeta::Data {
+            topic_index,
+            instance: 0,
+            ts,
+        };
+        self.write_record_parts(meta, bytes)
+    }
+
     pub fn emit_sync(&mut self) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>> {
         self.write_sync()
     }
+
+    pub fn unsubscribe<T>(
+        &mut self,
+        instance: u8,
+    ) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>>
+    where
+        T: ULogData + TopicOf<R>,
+    {
+        self.remove_subscription(<T as TopicOf<R>>::TOPIC.id(), instance)
+    }
+
+    pub fn emit_dropout(
+        &mut self,
+        duration_ms: u16,
+    ) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>> {
+        self.write_dropout(duration_ms)
+    }
+
+    // Writes a dropout still waiting for the next message and flushes the
+    // writer, so a log closed right after lost records still reports them.
+    pub fn flush(&mut self) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>> {
+        self.flush_dropout()?;
+        self.writer.flush().map_err(ExportError::Write)
+    }
+
+    // Unsubscribes streams of topics the new profile disables and records
+    // the profile name as a `log_profile` info message.
+    pub fn set_profile(
+        &mut self,
+        profile: LogProfile,
+    ) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>> {
+        self.profile = Some(profile);
+        for topic_index in 0..R::REGISTRY.len() {
+            let Ok(topic_index) = u16::try_from(topic_index) else {
+                break;
+            };
+            if profile.is_enabled(topic_index) {
+                continue;
+            }
+            for instance in 0..MAX_MULTI_IDS {
+                let Ok(instance) = u8::try_from(instance) else {
+                    break;
+                };
+                self.remove_subscription(topic_index, instance)?;
+            }
+        }
+        self.write_profile_info(profile)
+    }
+
+    pub fn emit_info(
+        &mut self,
+        name: &str,
+        value: InfoValue<'_>,
+    ) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>> {
+        self.write_info_value(name, value)
+    }
+
+    pub fn emit_multi_info(
+        &mut self,
+        name: &str,
+        value: InfoValue<'_>,
+        is_continued: bool,
+    ) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>> {
+        let key = value
+            .write_key::<{ u8::MAX as usize }>(name)
+            .ok_or(ExportError::MessageTooLarge)?;
+        let mut scratch = [0u8; 8];
+        let raw = value.value_bytes(&mut scratch);
+        self.write_message(wire::Message::multi_info(
+            is_continued,
+            key.as_bytes(),
+            raw,
+        )?)
+    }
+}
+
+impl<W, R, const RECORD_CAP: usize, const MAX_MULTI_IDS: usize, const MAX_STREAMS: usize>
+    ULogCoreExporter<W, R, StreamingReady, RECORD_CAP, MAX_MULTI_IDS, MAX_STREAMS>
+where
+    W: embedded_io::Write + embedded_io::Seek,
+    R: ULogRegistry,
+{
+    pub fn mark_appended_data(
+        &mut self,
+    ) -> Result<u64, ExportError<<W as embedded_io::ErrorType>::Error>> {
+        let offset = self.writer.stream_position().map_err(ExportError::Write)?;
+        let mut flag_bits = self.flag_bits;
+        if !flag_bits.push_appended_offset(offset) {
+            return Err(ExportError::AppendedOffsetsFull);
+        }
+
+        self.writer
+            .seek(embedded_io::SeekFrom::Start(wire::FLAG_BITS_PAYLOAD_OFFSET))
+            .map_err(ExportError::Write)?;
+        // Patching in place does not add to the log, so bypass the byte counters.
+        self.writer
+            .write_all(&flag_bits.encode())
+            .map_err(ExportError::Write)?;
+        self.writer
+            .seek(embedded_io::SeekFrom::Start(offset))
+            .map_err(ExportError::Write)?;
+        self.flag_bits = flag_bits;
+        Ok(offset)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{LogLevel, ULogData};
+    use crate::{LogLevel, ParameterValue};
 
     const CAP: usize = 32;
     const MI: usize = 8;
@@ -324,6 +813,67 @@ mod tests {
         }
     }
 
+    #[derive(Default)]
+    struct FailingSink {
+        bytes: std::vec::Vec<u8>,
+        fail: bool,
+    }
+
+    impl embedded_io::ErrorType for FailingSink {
+        type Error = embedded_io::ErrorKind;
+    }
+
+    impl embedded_io::Write for FailingSink {
+        fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            if self.fail {
+                return Err(embedded_io::ErrorKind::Other);
+            }
+            self.bytes.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct SeekSink {
+        bytes: std::vec::Vec<u8>,
+        pos: usize,
+    }
+
+    impl embedded_io::ErrorType for SeekSink {
+        type Error = core::convert::Infallible;
+    }
+
+    impl embedded_io::Write for SeekSink {
+        fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            let end = self.pos + buf.len();
+            if end > self.bytes.len() {
+                self.bytes.resize(end, 0);
+            }
+            self.bytes[self.pos..end].copy_from_slice(buf);
+            self.pos = end;
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    impl embedded_io::Seek for SeekSink {
+        fn seek(&mut self, pos: embedded_io::SeekFrom) -> Result<u64, Self::Error> {
+            self.pos = match pos {
+                embedded_io::SeekFrom::Start(offset) => offset as usize,
+                embedded_io::SeekFrom::End(offset) => (self.bytes.len() as i64 + offset) as usize,
+                embedded_io::SeekFrom::Current(offset) => (self.pos as i64 + offset) as usize,
+            };
+            Ok(self.pos as u64)
+        }
+    }
+
     struct Sample;
 
     impl ULogData for Sample {
@@ -350,12 +900,63 @@ mod tests {
         }]);
     }
 
+    impl crate::TopicOf<TestMessages> for Sample {
+        const TOPIC: crate::Topic<Self> = crate::Topic::new(0);
+    }
+
+    enum StatsMessages {}
+
+    impl crate::ULogRegistry for StatsMessages {
+        const REGISTRY: crate::Registry = crate::Registry::new(&[
+            crate::MessageMeta {
+                name: Sample::NAME,
+                format: Sample::FORMAT,
+                wire_size: Sample::WIRE_SIZE,
+            },
+            crate::MessageMeta {
+                name: ExportStatsData::NAME,
+                format: ExportStatsData::FORMAT,
+                wire_size: ExportStatsData::WIRE_SIZE,
+            },
+            crate::MessageMeta {
+                name: StreamStatsData::NAME,
+                format: StreamStatsData::FORMAT,
+                wire_size: StreamStatsData::WIRE_SIZE,
+            },
+        ]);
+    }
+
+    impl crate::TopicOf<StatsMessages> for ExportStatsData {
+        const TOPIC: crate::Topic<Self> = crate::Topic::new(1);
+    }
+
+    impl crate::TopicOf<StatsMessages> for StreamStatsData {
+        const TOPIC: crate::Topic<Self> = crate::Topic::new(2);
+    }
+
     enum EmptyMessages {}
 
     impl crate::ULogRegistry for EmptyMessages {
         const REGISTRY: crate::Registry = crate::Registry::new(&[]);
     }
 
+    enum NestedMessages {}
+
+    impl crate::ULogRegistry for NestedMessages {
+        const REGISTRY: crate::Registry = crate::Registry::with_nested(
+            &[crate::MessageMeta {
+                name: "outer",
+                format: "uint64_t timestamp;inner_s inner;",
+                wire_size: 9,
+            }],
+            &[&[crate::NestedMeta {
+                name: "inner_s",
+                format: "uint8_t x;",
+                nested: &[],
+            }]],
+        );
+    }
+
     #[test]
     fn core_start_then_accept() {
         let sink = VecSink::default();
@@ -379,6 +980,21 @@ mod tests {
         exporter.accept(rec).unwrap();
     }
 
+    #[test]
+    fn nested_formats_written_before_messages() {
+        let exporter = ULogCoreExporter::<_, NestedMessages, FormatsPending, CAP, MI, 64>::new(
+            VecSink::default(),
+        )
+        .start(100)
+        .unwrap();
+        let bytes = &exporter.writer.bytes;
+        let find = |needle: &[u8]| bytes.windows(needle.len()).position(|w| w == needle);
+
+        let nested = find(b"inner_s:uint8_t x;").unwrap();
+        let outer = find(b"outer:uint64_t timestamp;inner_s inner;").unwrap();
+        assert!(nested < outer);
+    }
+
     #[test]
     fn logs_string() {
         let sink = VecSink::default();
@@ -402,6 +1018,254 @@ mod tests {
         assert_eq!(exporter.dropped_streams(), 1);
     }
 
+    #[test]
+    fn discarded_data_is_counted_without_dropout() {
+        let sink = VecSink::default();
+        let mut exporter =
+            ULogCoreExporter::<_, TestMessages, FormatsPending, CAP, MI, 2>::new(sink)
+                .start(1_772_079_727_637)
+                .unwrap();
+        let kept = Record::new_data(0, 0, 1_000_000, &[1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+        let lost = |ts| Record::new_data(0, 3, ts, &[1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+
+        exporter.accept(kept.clone()).unwrap();
+        let before = exporter.writer_mut().bytes.len();
+        exporter.accept(lost(1_250_000)).unwrap();
+        exporter.accept(lost(1_500_000)).unwrap();
+        assert_eq!(exporter.dropped_streams(), 2);
+        exporter.accept(kept).unwrap();
+        assert_eq!(exporter.writer_mut().bytes[before + 2], b'D');
+
+        exporter.accept(Record::new_dropout(0x0102)).unwrap();
+        assert!(exporter.writer_mut().bytes.ends_with(&[2, 0, b'O', 2, 1]));
+    }
+
+    #[test]
+    fn flush_writes_pending_overrun_dropout() {
+        let mut queue = crate::RecordQueue::<CAP, 2>::new();
+        let (mut tx, mut rx) = queue.split();
+        let mut exporter = ULogCoreExporter::<_, TestMessages, FormatsPending, CAP, MI, 64>::new(
+            VecSink::default(),
+        )
+        .start(0)
+        .unwrap();
+        let log = |ts| Record::new_log(LogLevel::Info, None, ts, b"x");
+
+        tx.try_send(log(1)).unwrap();
+        assert!(tx.try_send(log(2)).is_err());
+        assert_eq!(exporter.drain(&mut rx), Ok(1));
+        assert!(exporter.writer_mut().bytes.ends_with(b"x"));
+
+        exporter.flush().unwrap();
+        assert!(exporter.writer_mut().bytes.ends_with(&[2, 0, b'O', 0, 0]));
+        let len = exporter.writer_mut().bytes.len();
+        exporter.flush().unwrap();
+        assert_eq!(exporter.writer_mut().bytes.len(), len);
+    }
+
+    #[test]
+    fn drain_reports_queue_overruns_as_dropout() {
+        let mut queue = crate::RecordQueue::<CAP, 2>::new();
+        let (mut tx, mut rx) = queue.split();
+        let mut exporter = ULogCoreExporter::<_, TestMessages, FormatsPending, CAP, MI, 64>::new(
+            VecSink::default(),
+        )
+        .start(1_772_079_727_637)
+        .unwrap();
+        let data = [1, 2, 3, 4, 5, 6, 7, 8];
+
+        tx.try_send(Record::new_data(0, 0, 1_000_000, &data).unwrap())
+            .unwrap();
+        assert_eq!(exporter.drain(&mut rx), Ok(1));
+
+        tx.try_send(Record::new_data(0, 0, 1_500_000, &data).unwrap())
+            .unwrap();
+        assert!(tx
+            .try_send(Record::new_data(0, 0, 1_600_000, &data).unwrap())
+            .is_err());
+        let before = exporter.writer_mut().bytes.len();
+        assert_eq!(exporter.drain(&mut rx), Ok(1));
+        assert_eq!(exporter.queue_overruns(), 1);
+        assert_eq!(exporter.writer_mut().bytes.len(), before + 13);
+
+        // The lost record followed the queued one, so the dropout comes next
+        // and spans up to the following record.
+        exporter
+            .accept(Record::new_data(0, 0, 1_800_000, &data).unwrap())
+            .unwrap();
+        let written = &exporter.writer_mut().bytes[before..];
+        assert_eq!(written[2], b'D');
+        assert_eq!(&written[13..18], &[2, 0, b'O', 0x2C, 0x01]);
+        assert_eq!(written[20], b'D');
+    }
+
+    #[test]
+    fn drain_keeps_overrun_dropout_after_write_error() {
+        let mut queue = crate::RecordQueue::<CAP, 2>::new();
+        let (mut tx, mut rx) = queue.split();
+        let mut exporter = ULogCoreExporter::<_, TestMessages, FormatsPending, CAP, MI, 64>::new(
+            FailingSink::default(),
+        )
+        .start(0)
+        .unwrap();
+        let log = |ts| Record::new_log(LogLevel::Info, None, ts, b"x");
+
+        tx.try_send(log(1)).unwrap();
+        assert!(tx.try_send(log(2)).is_err());
+        exporter.writer_mut().fail = true;
+        assert!(exporter.drain(&mut rx).is_err());
+        assert_eq!(exporter.queue_overruns(), 1);
+
+        exporter.writer_mut().fail = false;
+        let before = exporter.writer_mut().bytes.len();
+        exporter.accept(log(3)).unwrap();
+        assert_eq!(
+            &exporter.writer_mut().bytes[before..before + 3],
+            &[2, 0, b'O']
+        );
+    }
+
+    #[test]
+    fn drain_ring_matches_accept_output() {
+        let records = [
+            Record::<CAP>::new_data(0, 1, 10, &[1, 2, 3, 4, 5, 6, 7, 8]).unwrap(),
+            Record::<CAP>::new_log(LogLevel::Info, Some(4), 11, b"hello"),
+            Record::<CAP>::new_parameter(b"int32_t P", ParameterValue::I32(2)).unwrap(),
+        ];
+        let mut expected = ULogCoreExporter::<_, TestMessages, FormatsPending, CAP, MI, 64>::new(
+            VecSink::default(),
+        )
+        .start(100)
+        .unwrap();
+        for record in records.clone() {
+            expected.accept(record).unwrap();
+        }
+
+        let mut ring = crate::RecordRing::<128>::new();
+        let (mut tx, mut rx) = ring.split();
+        let mut exporter = ULogCoreExporter::<_, TestMessages, FormatsPending, CAP, MI, 64>::new(
+            VecSink::default(),
+        )
+        .start(100)
+        .unwrap();
+        for record in &records {
+            tx.try_send(record).unwrap();
+        }
+
+        assert_eq!(exporter.drain_ring(&mut rx), Ok(3));
+        assert_eq!(exporter.writer_mut().bytes, expected.writer_mut().bytes);
+        assert_eq!(exporter.drain_ring(&mut rx), Ok(0));
+    }
+
+    #[test]
+    fn drain_ring_reports_overruns_after_queued_records() {
+        let mut ring = crate::RecordRing::<64>::new();
+        let (mut tx, mut rx) = ring.split();
+        let mut exporter = ULogCoreExporter::<_, TestMessages, FormatsPending, CAP, MI, 64>::new(
+            VecSink::default(),
+        )
+        .start(0)
+        .unwrap();
+        let record = Record::<CAP>::new_data(0, 0, 10, &[1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+
+        let mut queued = 0;
+        while tx.try_send(&record).is_ok() {
+            queued += 1;
+        }
+        assert_eq!(exporter.drain_ring(&mut rx), Ok(queued));
+        assert_eq!(exporter.queue_overruns(), 1);
+        assert!(exporter.writer_mut().bytes.ends_with(&[8]));
+
+        let before = exporter.writer_mut().bytes.len();
+        exporter
+            .accept(Record::new_log(LogLevel::Info, None, 2_010, b"x"))
+            .unwrap();
+        assert_eq!(
+            &exporter.writer_mut().bytes[before..before + 5],
+            &[2, 0, b'O', 2, 0]
+        );
+    }
+
+    #[test]
+    fn drain_ring_skips_rejected_frames() {
+        let mut ring = crate::RecordRing::<128>::new();
+        let (mut tx, mut rx) = ring.split();
+        let mut exporter = ULogCoreExporter::<_, TestMessages, FormatsPending, CAP, MI, 64>::new(
+            VecSink::default(),
+        )
+        .start(0)
+        .unwrap();
+        let data = [1, 2, 3, 4, 5, 6, 7, 8];
+        let invalid = |topic_index, instance| RecordMeta::Data {
+            topic_index,
+            instance,
+            ts: 10,
+        };
+
+        tx.try_send_parts(invalid(9, 0), &data).unwrap();
+        tx.try_send_parts(invalid(0, MI as u8), &data).unwrap();
+        tx.try_send_parts(invalid(0, 0), &data).unwrap();
+        assert_eq!(exporter.drain_ring(&mut rx), Ok(1));
+        assert_eq!(exporter.rejected_records(), 2);
+        assert!(rx.is_empty());
+    }
+
+    #[test]
+    fn drain_ring_consumes_frame_on_write_error() {
+        let mut ring = crate::RecordRing::<128>::new();
+        let (mut tx, mut rx) = ring.split();
+        let mut exporter = ULogCoreExporter::<_, TestMessages, FormatsPending, CAP, MI, 64>::new(
+            FailingSink::default(),
+        )
+        .start(0)
+        .unwrap();
+        let record = Record::<CAP>::new_log(LogLevel::Info, None, 1, b"hello");
+
+        tx.try_send(&record).unwrap();
+        exporter.writer_mut().fail = true;
+        assert_eq!(
+            exporter.drain_ring(&mut rx),
+            Err(ExportError::Write(embedded_io::ErrorKind::Other))
+        );
+        assert!(rx.is_empty());
+        assert_eq!(exporter.rejected_records(), 0);
+    }
+
+    #[test]
+    fn unsubscribe_writes_remove_and_resubscribes() {
+        let sink = VecSink::default();
+        let mut exporter =
+            ULogCoreExporter::<_, TestMessages, FormatsPending, CAP, MI, 64>::new(sink)
+                .start(1_772_079_727_637)
+                .unwrap();
+        let data = [1, 2, 3, 4, 5, 6, 7, 8];
+
+        exporter.unsubscribe::<Sample>(1).unwrap();
+        assert!(exporter
+            .writer_mut()
+            .bytes
+            .ends_with(b"uint64_t timestamp;"));
+
+        exporter
+            .accept(Record::new_data(0, 1, 0, &data).unwrap())
+            .unwrap();
+        exporter.unsubscribe::<Sample>(1).unwrap();
+        assert!(exporter.writer_mut().bytes.ends_with(&[2, 0, b'R', 1, 0]));
+
+        exporter
+            .accept(Record::new_remove_subscription(0, 1))
+            .unwrap();
+        assert!(exporter.writer_mut().bytes.ends_with(&[2, 0, b'R', 1, 0]));
+
+        exporter
+            .accept(Record::new_data(0, 1, 0, &data).unwrap())
+            .unwrap();
+        assert!(exporter.writer_mut().bytes.ends_with(&[
+            9, 0, b'A', 1, 1, 0, b's', b'a', b'm', b'p', b'l', b'e', 10, 0, b'D', 1, 0, 1, 2, 3, 4,
+            5, 6, 7, 8
+        ]));
+    }
+
     #[test]
     fn writes_parameter_message() {
         let sink = VecSink::default();
@@ -488,6 +1352,281 @@ mod tests {
             .ends_with(&[6, 0, b'P', 1, b'k', 1, 0, 0, 0]));
     }
 
+    #[test]
+    fn startup_info_is_written_after_formats() {
+        let sink = VecSink::default();
+        let mut exporter =
+            ULogCoreExporter::<_, EmptyMessages, FormatsPending, CAP, MI, 64>::new(sink)
+                .with_info(&[("ver_sw", InfoValue::Str("abc")), ("t", InfoValue::I32(7))])
+                .start(1_772_079_727_637)
+                .unwrap();
+
+        assert!(exporter.writer_mut().bytes.ends_with(&[
+            18, 0, b'I', 14, b'c', b'h', b'a', b'r', b'[', b'3', b']', b' ', b'v', b'e', b'r',
+            b'_', b's', b'w', b'a', b'b', b'c', 14, 0, b'I', 9, b'i', b'n', b't', b'3', b'2', b'_',
+            b't', b' ', b't', 7, 0, 0, 0
+        ]));
+    }
+
+    #[test]
+    fn info_wire_bytes_match() {
+        let sink = VecSink::default();
+        let mut exporter =
+            ULogCoreExporter::<_, EmptyMessages, FormatsPending, CAP, MI, 64>::new(sink)
+                .start(1_772_079_727_637)
+                .unwrap();
+
+        exporter.emit_info("ver_sw", InfoValue::Str("abc")).unwrap();
+        assert!(exporter.writer_mut().bytes.ends_with(&[
+            18, 0, b'I', 14, b'c', b'h', b'a', b'r', b'[', b'3', b']', b' ', b'v', b'e', b'r',
+            b'_', b's', b'w', b'a', b'b', b'c'
+        ]));
+
+        let rec = Record::new_info(b"int32_t t", &7i32.to_le_bytes()).unwrap();
+        exporter.accept(rec).unwrap();
+        assert!(exporter.writer_mut().bytes.ends_with(&[
+            14, 0, b'I', 9, b'i', b'n', b't', b'3', b'2', b'_', b't', b' ', b't', 7, 0, 0, 0
+        ]));
+    }
+
+    #[test]
+    fn runtime_info_after_start_matches_with_info() {
+        const INFO: &[(&str, InfoValue<'static>)] = &[("ver_sw", InfoValue::Str("abc"))];
+        let mut configured = ULogCoreExporter::<_, TestMessages, FormatsPending, CAP, MI, 64>::new(
+            VecSink::default(),
+        )
+        .with_info(INFO)
+        .start(100)
+        .unwrap();
+        let serial = std::string::String::from("abc");
+        let mut runtime = ULogCoreExporter::<_, TestMessages, FormatsPending, CAP, MI, 64>::new(
+            VecSink::default(),
+        )
+        .start(100)
+        .unwrap();
+        runtime
+            .emit_info("ver_sw", InfoValue::Str(&serial))
+            .unwrap();
+
+        assert_eq!(runtime.writer_mut().bytes, configured.writer_mut().bytes);
+    }
+
+    #[test]
+    fn multi_info_wire_bytes_match() {
+        let sink = VecSink::default();
+        let mut exporter =
+            ULogCoreExporter::<_, EmptyMessages, FormatsPending, CAP, MI, 64>::new(sink)
+                .start(1_772_079_727_637)
+                .unwrap();
+
+        let rec = Record::new_multi_info(b"char[2] b", b"ok", true).unwrap();
+        exporter.accept(rec).unwrap();
+        assert!(exporter.writer_mut().bytes.ends_with(&[
+            13, 0, b'M', 1, 9, b'c', b'h', b'a', b'r', b'[', b'2', b']', b' ', b'b', b'o', b'k'
+        ]));
+
+        exporter
+            .emit_multi_info("b", InfoValue::Str("ok"), false)
+            .unwrap();
+        assert!(exporter.writer_mut().bytes.ends_with(&[
+            13, 0, b'M', 0, 9, b'c', b'h', b'a', b'r', b'[', b'2', b']', b' ', b'b', b'o', b'k'
+        ]));
+    }
+
+    #[test]
+    fn parameter_default_wire_bytes_match() {
+        let sink = VecSink::default();
+        let mut exporter =
+            ULogCoreExporter::<_, EmptyMessages, FormatsPending, CAP, MI, 64>::new(sink)
+                .start(1_772_079_727_637)
+                .unwrap();
+        let rec = Record::new_parameter_default(
+            b"k",
+            ParameterValue::I32(1),
+            crate::DefaultTypes::CONFIGURATION,
+        )
+        .unwrap();
+
+        exporter.accept(rec).unwrap();
+        assert!(exporter
+            .writer_mut()
+            .bytes
+            .ends_with(&[7, 0, b'Q', 2, 1, b'k', 1, 0, 0, 0]));
+    }
+
+    #[test]
+    fn flag_bits_are_written_and_patched_for_appended_data() {
+        let mut flags = FlagBits::new();
+        flags.compat_flags[0] = FlagBits::COMPAT_DEFAULT_PARAMETERS;
+        let mut exporter = ULogCoreExporter::<_, EmptyMessages, FormatsPending, CAP, MI, 64>::new(
+            SeekSink::default(),
+        )
+        .with_flag_bits(flags)
+        .start(1)
+        .unwrap();
+        assert_eq!(&exporter.writer_mut().bytes[16..20], &[40, 0, b'B', 1]);
+
+        exporter
+            .accept(Record::new_log(LogLevel::Info, None, 1, b"main"))
+            .unwrap();
+        let offset = exporter.mark_appended_data().unwrap();
+        assert_eq!(offset, exporter.writer_mut().bytes.len() as u64);
+        assert!(exporter.flag_bits().has_appended_data());
+
+        exporter
+            .accept(Record::new_log(LogLevel::Info, None, 2, b"crash"))
+            .unwrap();
+        let total_bytes = exporter.stats().total_bytes;
+        let bytes = &exporter.writer_mut().bytes;
+        assert_eq!(total_bytes, bytes.len() as u64);
+        assert_eq!(bytes[19 + 8], FlagBits::INCOMPAT_DATA_APPENDED);
+        assert_eq!(&bytes[35..43], &offset.to_le_bytes());
+        assert!(bytes.ends_with(b"crash"));
+        assert_eq!(bytes.len() as u64, offset + 17);
+    }
+
+    #[test]
+    fn stats_count_streams_logs_and_bytes() {
+        let mut exporter = ULogCoreExporter::<_, StatsMessages, FormatsPending, CAP, MI, 64>::new(
+            VecSink::default(),
+        )
+        .start(0)
+        .unwrap();
+        for ts in 1..3 {
+            exporter
+                .accept(Record::new_data(0, 1, ts, &[0; 8]).unwrap())
+                .unwrap();
+        }
+        exporter
+            .accept(Record::new_log(LogLevel::Warning, None, 3, b"low"))
+            .unwrap();
+
+        let stats = *exporter.stats();
+        assert_eq!(
+            stats.streams[1],
+            crate::StreamStats {
+                messages: 2,
+                bytes: 26
+            }
+        );
+        assert_eq!(stats.logs(LogLevel::Warning), 1);
+        assert_eq!(stats.total_bytes, exporter.writer_mut().bytes.len() as u64);
+
+        exporter.emit_stats(4).unwrap();
+        let bytes = exporter.writer_mut().bytes.clone();
+        let mut reader = crate::ULogReader::<_>::new(bytes.as_slice());
+        let mut stream_slots = std::vec::Vec::new();
+        while let Some(event) = reader.next_event().unwrap() {
+            match event {
+                crate::ULogEvent::Data { msg_id: 8, data } => {
+                    let total_bytes = u64::from_le_bytes(data[8..16].try_into().unwrap());
+                    assert_eq!(total_bytes, stats.total_bytes);
+                }
+                crate::ULogEvent::Data { msg_id: 16, data } => {
+                    stream_slots.push(u16::from_le_bytes([data[20], data[21]]));
+                }
+                _ => {}
+            }
+        }
+        // Only streams active before `emit_stats` are reported.
+        assert_eq!(stream_slots, [1]);
+    }
+
+    #[test]
+    fn profile_disables_topics_at_runtime() {
+        let high_rate = LogProfile::all("high_rate");
+        let quiet = LogProfile::all("quiet").disable::<TestMessages, Sample>();
+        let mut exporter = ULogCoreExporter::<_, TestMessages, FormatsPending, CAP, MI, 64>::new(
+            VecSink::default(),
+        )
+        .with_profile(high_rate)
+        .start(0)
+        .unwrap();
+
+        let data = || Record::new_data(0, 1, 10, &[0; 8]).unwrap();
+        exporter.accept(data()).unwrap();
+        exporter.set_profile(quiet).unwrap();
+        exporter.accept(data()).unwrap();
+        exporter.set_profile(high_rate).unwrap();
+        exporter.accept(data()).unwrap();
+
+        let bytes = exporter.writer_mut().bytes.clone();
+        let mut reader = crate::ULogReader::<_>::new(bytes.as_slice());
+        let mut events = std::vec::Vec::new();
+        while let Some(event) = reader.next_event().unwrap() {
+            match event {
+                crate::ULogEvent::Info { key, value } => {
+                    assert_eq!(key, std::format!("char[{}] log_profile", value.len()));
+                    events.push(std::str::from_utf8(value).unwrap().to_owned());
+                }
+                crate::ULogEvent::AddSubscription { .. } => events.push("A".into()),
+                crate::ULogEvent::RemoveSubscription { .. } => events.push("R".into()),
+                crate::ULogEvent::Data { .. } => events.push("D".into()),
+                _ => {}
+            }
+        }
+        assert_eq!(
+            events,
+            ["high_rate", "A", "D", "R", "quiet", "high_rate", "A", "D"]
+        );
+    }
+
+    #[test]
+    fn level_filter_drops_logs_below_threshold() {
+        let mut exporter = ULogCoreExporter::<_, EmptyMessages, FormatsPending, CAP, MI, 64>::new(
+            VecSink::default(),
+        )
+        .with_level_filter(LevelFilter::new(LogLevel::Warning))
+        .start(0)
+        .unwrap();
+        let startup_len = exporter.writer_mut().bytes.len();
+
+        exporter
+            .accept(Record::new_log(LogLevel::Debug, None, 1, b"noisy"))
+            .unwrap();
+        assert_eq!(exporter.writer_mut().bytes.len(), startup_len);
+
+        exporter.set_level_filter(LevelFilter::new(LogLevel::Debug));
+        exporter
+            .accept(Record::new_log(LogLevel::Debug, None, 2, b"noisy"))
+            .unwrap();
+        assert!(exporter.writer_mut().bytes.ends_with(b"noisy"));
+        assert_eq!(exporter.stats().logs(LogLevel::Debug), 1);
+    }
+
+    fn count_syncs(bytes: &[u8]) -> usize {
+        bytes
+            .windows(wire::ULOG_SYNC_MAGIC.len())
+            .filter(|window| *window == wire::ULOG_SYNC_MAGIC)
+            .count()
+    }
+
+    #[test]
+    fn sync_policy_inserts_sync_messages() {
+        let mut by_bytes = ULogCoreExporter::<_, EmptyMessages, FormatsPending, CAP, MI, 64>::new(
+            VecSink::default(),
+        )
+        .with_sync_policy(SyncPolicy::EveryBytes(64))
+        .start(0)
+        .unwrap();
+        let mut by_time = ULogCoreExporter::<_, EmptyMessages, FormatsPending, CAP, MI, 64>::new(
+            VecSink::default(),
+        )
+        .with_sync_policy(SyncPolicy::EveryMicros(1_000))
+        .start(0)
+        .unwrap();
+
+        for ts in (0..5_000).step_by(250) {
+            let record = Record::new_log(LogLevel::Info, None, ts, b"tick");
+            by_bytes.accept(record.clone()).unwrap();
+            by_time.accept(record).unwrap();
+        }
+
+        // 59 startup bytes, then 16 bytes per log and 11 per sync.
+        assert_eq!(count_syncs(&by_bytes.writer_mut().bytes), 5);
+        assert_eq!(count_syncs(&by_time.writer_mut().bytes), 4);
+    }
+
     #[test]
     fn test_sync_message() {
         let sink = VecSink::default();
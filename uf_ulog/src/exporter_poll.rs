@@ -0,0 +1,793 @@
+use crate::exporter::{CoreState, StreamingReady};
+use crate::wire;
+use crate::{
+    ExportError, ExportStats, ExportStatsData, ExportStep, FlagBits, FormatsPending, InfoValue,
+    LevelFilter, LogProfile, NestedFormats, Record, RecordMeta, RecordReceiver, RingReader,
+    StreamStatsData, SyncPolicy, TopicOf, ULogCoreExporter, ULogData, ULogRegistry,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PendingFull;
+
+impl embedded_io::Error for PendingFull {
+    fn kind(&self) -> embedded_io::ErrorKind {
+        embedded_io::ErrorKind::OutOfMemory
+    }
+}
+
+pub(crate) struct PendingBytes<const N: usize> {
+    bytes: heapless::Vec<u8, N>,
+}
+
+impl<const N: usize> embedded_io::ErrorType for PendingBytes<N> {
+    type Error = PendingFull;
+}
+
+impl<const N: usize> embedded_io::Write for PendingBytes<N> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.bytes.extend_from_slice(buf).map_err(|_| PendingFull)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+enum Startup {
+    Header,
+    FlagBits,
+    NestedFormats(NestedFormats),
+    Formats(usize),
+    Info(usize),
+    Profile,
+}
+
+pub struct ULogPollExporter<
+    W,
+    R: ULogRegistry,
+    const RECORD_CAP: usize = 128,
+    const MAX_MULTI_IDS: usize = 4,
+    const MAX_STREAMS: usize = 128,
+    const PENDING_CAP: usize = 512,
+> {
+    writer: W,
+    core: ULogCoreExporter<
+        PendingBytes<PENDING_CAP>,
+        R,
+        StreamingReady,
+        RECORD_CAP,
+        MAX_MULTI_IDS,
+        MAX_STREAMS,
+    >,
+    written: usize,
+    startup: Option<Startup>,
+    start_timestamp: u64,
+    // Overruns taken from a queue but not yet reported, because the records
+    // queued before them are still being drained.
+    overruns: u32,
+}
+
+impl<
+        W,
+        R,
+        const RECORD_CAP: usize,
+        const MAX_MULTI_IDS: usize,
+        const MAX_STREAMS: usize,
+        const PENDING_CAP: usize,
+    > ULogPollExporter<W, R, RECORD_CAP, MAX_MULTI_IDS, MAX_STREAMS, PENDING_CAP>
+where
+    W: embedded_io::Write + embedded_io::WriteReady,
+    R: ULogRegistry,
+{
+    // Every startup message is staged whole, so the largest one must fit.
+    const STARTUP_FITS: () = assert!(
+        wire::MESSAGE_HEADER_LEN + wire::FLAG_BITS_PAYLOAD_LEN <= PENDING_CAP
+            && wire::MESSAGE_HEADER_LEN + R::REGISTRY.max_format_len() <= PENDING_CAP,
+        "PENDING_CAP is too small for the ULog startup messages"
+    );
+
+    pub fn new(writer: W, timestamp_micros: u64) -> Self {
+        let () = Self::STARTUP_FITS;
+        let core =
+            ULogCoreExporter::<_, R, FormatsPending, RECORD_CAP, MAX_MULTI_IDS, MAX_STREAMS>::new(
+                PendingBytes {
+                    bytes: heapless::Vec::new(),
+                },
+            );
+        Self {
+            writer,
+            core: core.into_state(),
+            written: 0,
+            startup: Some(Startup::Header),
+            start_timestamp: timestamp_micros,
+            overruns: 0,
+        }
+    }
+
+    // The builders mirror `ULogCoreExporter` and must be applied before the
+    // first `poll` starts writing the startup messages.
+    pub fn with_flag_bits(self, flag_bits: FlagBits) -> Self {
+        self.configure(|core| core.with_flag_bits(flag_bits))
+    }
+
+    pub fn with_sync_policy(self, sync_policy: SyncPolicy) -> Self {
+        self.configure(|core| core.with_sync_policy(sync_policy))
+    }
+
+    pub fn with_profile(self, profile: LogProfile) -> Self {
+        Self::assert_info_fits("log_profile", InfoValue::Str(profile.name()));
+        self.configure(|core| core.with_profile(profile))
+    }
+
+    pub fn with_level_filter(self, level_filter: LevelFilter) -> Self {
+        self.configure(|core| core.with_level_filter(level_filter))
+    }
+
+    // Panics if an info message does not fit `PENDING_CAP`. Runtime values go
+    // through `emit_info` before the first record, as with the core exporter.
+    pub fn with_info(self, info: &'static [(&'static str, InfoValue<'static>)]) -> Self {
+        for &(name, value) in info {
+            Self::assert_info_fits(name, value);
+        }
+        self.configure(|core| core.with_info(info))
+    }
+
+    fn assert_info_fits(name: &str, value: InfoValue<'_>) {
+        let mut scratch = [0u8; 8];
+        let value_len = value.value_bytes(&mut scratch).len();
+        let fits = value
+            .write_key::<{ u8::MAX as usize }>(name)
+            .is_some_and(|key| wire::MESSAGE_HEADER_LEN + 1 + key.len() + value_len <= PENDING_CAP);
+        assert!(fits, "info message does not fit PENDING_CAP");
+    }
+
+    fn configure(
+        mut self,
+        build: impl FnOnce(
+            ULogCoreExporter<
+                PendingBytes<PENDING_CAP>,
+                R,
+                FormatsPending,
+                RECORD_CAP,
+                MAX_MULTI_IDS,
+                MAX_STREAMS,
+            >,
+        ) -> ULogCoreExporter<
+            PendingBytes<PENDING_CAP>,
+            R,
+            FormatsPending,
+            RECORD_CAP,
+            MAX_MULTI_IDS,
+            MAX_STREAMS,
+        >,
+    ) -> Self {
+        self.core = build(self.core.into_state()).into_state();
+        self
+    }
+
+    pub fn writer_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    pub fn dropped_streams(&self) -> u32 {
+        self.core.dropped_streams()
+    }
+
+    pub fn queue_overruns(&self) -> u32 {
+        self.core.queue_overruns().saturating_add(self.overruns)
+    }
+
+    pub fn rejected_records(&self) -> u32 {
+        self.core.rejected_records()
+    }
+
+    pub fn stats(&self) -> &ExportStats<MAX_STREAMS> {
+        self.core.stats()
+    }
+
+    pub fn profile(&self) -> Option<LogProfile> {
+        self.core.profile()
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.startup.is_none() && self.pending().is_empty()
+    }
+
+    // Borrows the record so the caller still holds it after `Busy` and can
+    // retry once `poll` has written the pending message.
+    pub fn accept(
+        &mut self,
+        record: &Record<RECORD_CAP>,
+    ) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>> {
+        if !self.is_ready() {
+            return Err(ExportError::Busy);
+        }
+        let state = self.core.snapshot();
+        let result = self.core.write_record_parts(record.meta(), record.bytes());
+        self.finish_fill(result, state)
+    }
+
+    // Stages at most one queued record per call, so `Ok(0)` while a message is
+    // pending. Overruns are reported once the queue has been drained.
+    pub fn drain(
+        &mut self,
+        receiver: &mut RecordReceiver<'_, RECORD_CAP>,
+    ) -> Result<usize, ExportError<<W as embedded_io::ErrorType>::Error>> {
+        if !self.is_ready() {
+            return Ok(0);
+        }
+        self.overruns = self.overruns.saturating_add(receiver.take_overruns());
+        let record = receiver.try_recv();
+        self.stage_drained(
+            record
+                .as_ref()
+                .map(|record| (record.meta(), record.bytes())),
+        )
+    }
+
+    pub fn drain_ring<const N: usize>(
+        &mut self,
+        reader: &mut RingReader<'_, N>,
+    ) -> Result<usize, ExportError<<W as embedded_io::ErrorType>::Error>> {
+        if !self.is_ready() {
+            return Ok(0);
+        }
+        self.overruns = self.overruns.saturating_add(reader.take_overruns());
+        let result = self.stage_drained(reader.peek());
+        reader.consume();
+        result
+    }
+
+    fn stage_drained(
+        &mut self,
+        next: Option<(RecordMeta, &[u8])>,
+    ) -> Result<usize, ExportError<<W as embedded_io::ErrorType>::Error>> {
+        let state = self.core.snapshot();
+        let overruns = if next.is_none() {
+            core::mem::take(&mut self.overruns)
+        } else {
+            0
+        };
+        let mut next = next;
+        let result = self.core.drain_with(overruns, |core| {
+            next.take()
+                .map(|(meta, bytes)| core.write_record_parts(meta, bytes))
+        });
+        if result.is_err() {
+            self.overruns = self.overruns.saturating_add(overruns);
+        }
+        self.finish_fill(result, state)
+    }
+
+    pub fn emit_sync(&mut self) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>> {
+        if !self.is_ready() {
+            return Err(ExportError::Busy);
+        }
+        let state = self.core.snapshot();
+        let result = self.core.emit_sync();
+        self.finish_fill(result, state)
+    }
+
+    pub fn emit_info(
+        &mut self,
+        name: &str,
+        value: InfoValue<'_>,
+    ) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>> {
+        if !self.is_ready() {
+            return Err(ExportError::Busy);
+        }
+        let state = self.core.snapshot();
+        let result = self.core.emit_info(name, value);
+        self.finish_fill(result, state)
+    }
+
+    pub fn unsubscribe<T>(
+        &mut self,
+        instance: u8,
+    ) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>>
+    where
+        T: ULogData + TopicOf<R>,
+    {
+        if !self.is_ready() {
+            return Err(ExportError::Busy);
+        }
+        let state = self.core.snapshot();
+        let result = self.core.unsubscribe::<T>(instance);
+        self.finish_fill(result, state)
+    }
+
+    // All removals and the profile info are staged together, so they must
+    // fit `PENDING_CAP` at once.
+    pub fn set_profile(
+        &mut self,
+        profile: LogProfile,
+    ) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>> {
+        if !self.is_ready() {
+            return Err(ExportError::Busy);
+        }
+        let state = self.core.snapshot();
+        let result = self.core.set_profile(profile);
+        self.finish_fill(result, state)
+    }
+
+    // The summary and every active stream row are staged together, so they
+    // must fit `PENDING_CAP` at once.
+    // Stages the pending dropout, including overruns not yet reported; `poll`
+    // then writes it out before the log is closed.
+    pub fn flush(&mut self) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>> {
+        if !self.is_ready() {
+            return Err(ExportError::Busy);
+        }
+        let state = self.core.snapshot();
+        let overruns = core::mem::take(&mut self.overruns);
+        let result = self
+            .core
+            .drain_with(overruns, |_| None)
+            .and_then(|_| self.core.flush());
+        if result.is_err() {
+            self.overruns = self.overruns.saturating_add(overruns);
+        }
+        self.finish_fill(result, state)
+    }
+
+    pub fn emit_stats(
+        &mut self,
+        timestamp: u64,
+    ) -> Result<(), ExportError<<W as embedded_io::ErrorType>::Error>>
+    where
+        ExportStatsData: TopicOf<R>,
+        StreamStatsData: TopicOf<R>,
+    {
+        if !self.is_ready() {
+            return Err(ExportError::Busy);
+        }
+        let state = self.core.snapshot();
+        let result = self.core.emit_stats(timestamp);
+        self.finish_fill(result, state)
+    }
+
+    pub fn poll(
+        &mut self,
+    ) -> Result<ExportStep, ExportError<<W as embedded_io::ErrorType>::Error>> {
+        if self.pending().is_empty() && !self.fill_startup()? {
+            return Ok(ExportStep::Idle);
+        }
+        if !self.writer.write_ready().map_err(ExportError::Write)? {
+            return Ok(ExportStep::Idle);
+        }
+
+        let pending = &self.core.writer().bytes;
+        let written = self
+            .writer
+            .write(&pending[self.written..])
+            .map_err(ExportError::Write)?;
+        if written == 0 {
+            return Ok(ExportStep::Idle);
+        }
+
+        self.written += written;
+        if self.written == self.core.writer_mut().bytes.len() {
+            self.core.writer_mut().bytes.clear();
+            self.written = 0;
+        }
+        Ok(ExportStep::Progressed)
+    }
+
+    fn pending(&self) -> &[u8] {
+        &self.core.writer().bytes[self.written..]
+    }
+
+    fn fill_startup(&mut self) -> Result<bool, ExportError<<W as embedded_io::ErrorType>::Error>> {
+        let state = self.core.snapshot();
+        loop {
+            let Some(step) = self.startup.clone() else {
+                return Ok(false);
+            };
+            let (result, next) = match step {
+                Startup::Header => (
+                    self.core.write_header(self.start_timestamp),
+                    Some(Startup::FlagBits),
+                ),
+                Startup::FlagBits => (
+                    self.core.write_flag_bits(),
+                    Some(Startup::NestedFormats(R::REGISTRY.nested_formats())),
+                ),
+                Startup::NestedFormats(mut formats) => {
+                    let Some(meta) = formats.next() else {
+                        self.startup = Some(Startup::Formats(0));
+                        continue;
+                    };
+                    (
+                        self.core.write_format(meta.name, meta.format),
+                        Some(Startup::NestedFormats(formats)),
+                    )
+                }
+                Startup::Formats(index) => {
+                    let Some(meta) = R::REGISTRY.get(index) else {
+                        self.startup = Some(Startup::Info(0));
+                        continue;
+                    };
+                    (
+                        self.core.write_format(meta.name, meta.format),
+                        Some(Startup::Formats(index + 1)),
+                    )
+                }
+                Startup::Info(index) => {
+                    let Some(&(name, value)) = self.core.info().get(index) else {
+                        self.startup = Some(Startup::Profile);
+                        continue;
+                    };
+                    (
+                        self.core.write_info_value(name, value),
+                        Some(Startup::Info(index + 1)),
+                    )
+                }
+                Startup::Profile => {
+                    let Some(profile) = self.core.profile() else {
+                        self.startup = None;
+                        return Ok(false);
+                    };
+                    (self.core.write_profile_info(profile), None)
+                }
+            };
+            self.finish_fill(result, state)?;
+            self.startup = next;
+            return Ok(true);
+        }
+    }
+
+    fn finish_fill<T>(
+        &mut self,
+        result: Result<T, ExportError<PendingFull>>,
+        state: CoreState<MAX_STREAMS>,
+    ) -> Result<T, ExportError<<W as embedded_io::ErrorType>::Error>> {
+        result.map_err(|err| {
+            // Never leave a partial message or its bookkeeping behind.
+            self.core.restore(state);
+            self.core.writer_mut().bytes.clear();
+            self.written = 0;
+            match err {
+                ExportError::Write(PendingFull) => ExportError::MessageTooLarge,
+                ExportError::InvalidTopicIndex => ExportError::InvalidTopicIndex,
+                ExportError::InvalidWireSize => ExportError::InvalidWireSize,
+                ExportError::InvalidMultiId => ExportError::InvalidMultiId,
+                ExportError::TooManyStreams => ExportError::TooManyStreams,
+                ExportError::MessageTooLarge => ExportError::MessageTooLarge,
+                ExportError::AppendedOffsetsFull => ExportError::AppendedOffsetsFull,
+                ExportError::Busy => ExportError::Busy,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LogLevel, Record, ULogData};
+
+    const CAP: usize = 32;
+    const MI: usize = 8;
+
+    #[derive(Default)]
+    struct VecSink {
+        bytes: std::vec::Vec<u8>,
+    }
+
+    impl embedded_io::ErrorType for VecSink {
+        type Error = core::convert::Infallible;
+    }
+
+    impl embedded_io::Write for VecSink {
+        fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            self.bytes.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct SlowSink {
+        bytes: std::vec::Vec<u8>,
+        busy: bool,
+    }
+
+    impl embedded_io::ErrorType for SlowSink {
+        type Error = core::convert::Infallible;
+    }
+
+    impl embedded_io::Write for SlowSink {
+        fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            let len = buf.len().min(3);
+            self.bytes.extend_from_slice(&buf[..len]);
+            Ok(len)
+        }
+
+        fn flush(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    impl embedded_io::WriteReady for SlowSink {
+        fn write_ready(&mut self) -> Result<bool, Self::Error> {
+            self.busy = !self.busy;
+            Ok(!self.busy)
+        }
+    }
+
+    struct Sample;
+
+    impl ULogData for Sample {
+        const FORMAT: &'static str = "uint64_t timestamp;";
+        const NAME: &'static str = "sample";
+        const WIRE_SIZE: usize = 8;
+
+        fn encode(&self, _buf: &mut [u8]) -> Result<usize, crate::EncodeError> {
+            Ok(8)
+        }
+
+        fn timestamp(&self) -> u64 {
+            0
+        }
+    }
+
+    enum TestMessages {}
+
+    impl crate::ULogRegistry for TestMessages {
+        const REGISTRY: crate::Registry = crate::Registry::new(&[crate::MessageMeta {
+            name: Sample::NAME,
+            format: Sample::FORMAT,
+            wire_size: Sample::WIRE_SIZE,
+        }]);
+    }
+
+    impl TopicOf<TestMessages> for Sample {
+        const TOPIC: crate::Topic<Self> = crate::Topic::new(0);
+    }
+
+    fn records() -> [Record<CAP>; 2] {
+        [
+            Record::new_data(0, 1, 0, &[1, 2, 3, 4, 5, 6, 7, 8]).unwrap(),
+            Record::new_log(LogLevel::Info, None, 1, b"hello"),
+        ]
+    }
+
+    fn drain(exporter: &mut ULogPollExporter<SlowSink, TestMessages, CAP, MI, 64, 64>) -> usize {
+        let mut polls = 0;
+        while !exporter.is_ready() {
+            exporter.poll().unwrap();
+            polls += 1;
+        }
+        polls
+    }
+
+    #[test]
+    fn poll_matches_blocking_exporter_output() {
+        let mut blocking = ULogCoreExporter::<_, TestMessages, FormatsPending, CAP, MI, 64>::new(
+            VecSink::default(),
+        )
+        .start(100)
+        .unwrap();
+        for record in records() {
+            blocking.accept(record).unwrap();
+        }
+
+        let mut exporter =
+            ULogPollExporter::<_, TestMessages, CAP, MI, 64, 64>::new(SlowSink::default(), 100);
+        assert!(!exporter.is_ready());
+        assert!(drain(&mut exporter) > 1);
+        for record in records() {
+            exporter.accept(&record).unwrap();
+            drain(&mut exporter);
+        }
+
+        assert_eq!(exporter.poll(), Ok(ExportStep::Idle));
+        assert_eq!(exporter.writer_mut().bytes, blocking.writer_mut().bytes);
+    }
+
+    #[test]
+    fn poll_startup_applies_core_configuration() {
+        let profile = LogProfile::all("replay");
+        let info = &[("ver_sw", InfoValue::Str("abc"))];
+        let mut flag_bits = FlagBits::new();
+        flag_bits.compat_flags[0] = FlagBits::COMPAT_DEFAULT_PARAMETERS;
+        let mut blocking = ULogCoreExporter::<_, TestMessages, FormatsPending, CAP, MI, 64>::new(
+            VecSink::default(),
+        )
+        .with_flag_bits(flag_bits)
+        .with_sync_policy(SyncPolicy::EveryBytes(16))
+        .with_profile(profile)
+        .with_info(info)
+        .start(100)
+        .unwrap();
+        for record in records() {
+            blocking.accept(record).unwrap();
+        }
+
+        let mut exporter =
+            ULogPollExporter::<_, TestMessages, CAP, MI, 64, 64>::new(SlowSink::default(), 100)
+                .with_flag_bits(flag_bits)
+                .with_sync_policy(SyncPolicy::EveryBytes(16))
+                .with_profile(profile)
+                .with_info(info);
+        drain(&mut exporter);
+        for record in records() {
+            exporter.accept(&record).unwrap();
+            drain(&mut exporter);
+        }
+
+        assert_eq!(exporter.writer_mut().bytes, blocking.writer_mut().bytes);
+    }
+
+    #[test]
+    fn drain_stages_one_record_at_a_time() {
+        let data = [1, 2, 3, 4, 5, 6, 7, 8];
+        let send_all = |tx: &mut crate::RecordSender<'_, CAP>| {
+            for ts in [10, 20, 30] {
+                let _ = tx.try_send(Record::new_data(0, 0, ts, &data).unwrap());
+            }
+        };
+
+        let mut queue = crate::RecordQueue::<CAP, 3>::new();
+        let (mut tx, mut rx) = queue.split();
+        let mut blocking = ULogCoreExporter::<_, TestMessages, FormatsPending, CAP, MI, 64>::new(
+            VecSink::default(),
+        )
+        .start(100)
+        .unwrap();
+        send_all(&mut tx);
+        assert_eq!(blocking.drain(&mut rx), Ok(2));
+
+        let mut queue = crate::RecordQueue::<CAP, 3>::new();
+        let (mut tx, mut rx) = queue.split();
+        let mut exporter =
+            ULogPollExporter::<_, TestMessages, CAP, MI, 64, 64>::new(SlowSink::default(), 100);
+        send_all(&mut tx);
+        assert_eq!(exporter.drain(&mut rx), Ok(0));
+        drain(&mut exporter);
+        assert_eq!(exporter.drain(&mut rx), Ok(1));
+        assert_eq!(exporter.drain(&mut rx), Ok(0));
+        drain(&mut exporter);
+        assert_eq!(exporter.drain(&mut rx), Ok(1));
+        drain(&mut exporter);
+        assert_eq!(exporter.queue_overruns(), 1);
+        assert_eq!(exporter.drain(&mut rx), Ok(0));
+        drain(&mut exporter);
+
+        assert_eq!(exporter.queue_overruns(), 1);
+        assert_eq!(exporter.writer_mut().bytes, blocking.writer_mut().bytes);
+
+        blocking.flush().unwrap();
+        exporter.flush().unwrap();
+        assert_eq!(exporter.flush(), Err(ExportError::Busy));
+        drain(&mut exporter);
+        assert!(exporter.writer_mut().bytes.ends_with(&[2, 0, b'O', 0, 0]));
+        assert_eq!(exporter.writer_mut().bytes, blocking.writer_mut().bytes);
+    }
+
+    #[test]
+    fn drain_ring_skips_rejected_frames() {
+        let mut ring = crate::RecordRing::<128>::new();
+        let (mut tx, mut rx) = ring.split();
+        let mut exporter =
+            ULogPollExporter::<_, TestMessages, CAP, MI, 64, 64>::new(SlowSink::default(), 100);
+        drain(&mut exporter);
+        let meta = RecordMeta::Data {
+            topic_index: 9,
+            instance: 0,
+            ts: 10,
+        };
+
+        tx.try_send_parts(meta, &[0; 8]).unwrap();
+        assert_eq!(exporter.drain_ring(&mut rx), Ok(0));
+        assert_eq!(exporter.rejected_records(), 1);
+        assert!(rx.is_empty());
+        assert!(exporter.is_ready());
+    }
+
+    #[test]
+    fn runtime_messages_match_blocking_exporter() {
+        let mut blocking = ULogCoreExporter::<_, TestMessages, FormatsPending, CAP, MI, 64>::new(
+            VecSink::default(),
+        )
+        .start(100)
+        .unwrap();
+        let [data, _] = records();
+        blocking.accept(data.clone()).unwrap();
+        blocking.emit_info("ver_sw", InfoValue::Str("abc")).unwrap();
+        blocking.unsubscribe::<Sample>(1).unwrap();
+        blocking.set_profile(LogProfile::none("off")).unwrap();
+
+        let mut exporter =
+            ULogPollExporter::<_, TestMessages, CAP, MI, 64, 64>::new(SlowSink::default(), 100);
+        drain(&mut exporter);
+        exporter.accept(&data).unwrap();
+        assert_eq!(
+            exporter.emit_info("ver_sw", InfoValue::Str("abc")),
+            Err(ExportError::Busy)
+        );
+        drain(&mut exporter);
+        exporter.emit_info("ver_sw", InfoValue::Str("abc")).unwrap();
+        drain(&mut exporter);
+        exporter.unsubscribe::<Sample>(1).unwrap();
+        drain(&mut exporter);
+        exporter.set_profile(LogProfile::none("off")).unwrap();
+        drain(&mut exporter);
+
+        assert_eq!(exporter.writer_mut().bytes, blocking.writer_mut().bytes);
+        assert_eq!(exporter.profile(), Some(LogProfile::none("off")));
+    }
+
+    #[test]
+    #[should_panic(expected = "PENDING_CAP")]
+    fn oversized_startup_info_is_rejected() {
+        let _ = ULogPollExporter::<_, TestMessages, CAP, MI, 64, 64>::new(SlowSink::default(), 100)
+            .with_info(&[(
+                "ver_sw",
+                InfoValue::Str(concat!(
+                    "0123456789abcdef0123456789abcdef",
+                    "0123456789abcdef0123456789abcdef"
+                )),
+            )]);
+    }
+
+    #[test]
+    fn accept_is_busy_while_message_pending() {
+        let mut exporter =
+            ULogPollExporter::<_, TestMessages, CAP, MI, 64, 64>::new(SlowSink::default(), 100);
+        let [data, log] = records();
+        assert_eq!(exporter.accept(&data), Err(ExportError::Busy));
+
+        // The rejected record is still the caller's to retry.
+        drain(&mut exporter);
+        exporter.accept(&data).unwrap();
+        assert_eq!(exporter.accept(&log), Err(ExportError::Busy));
+        drain(&mut exporter);
+        exporter.accept(&log).unwrap();
+        assert_eq!(exporter.emit_sync(), Err(ExportError::Busy));
+    }
+
+    enum WideMessages {}
+
+    impl crate::ULogRegistry for WideMessages {
+        const REGISTRY: crate::Registry = crate::Registry::new(&[crate::MessageMeta {
+            name: "wide",
+            format: "uint64_t timestamp;uint8_t[32] pad;",
+            wire_size: 40,
+        }]);
+    }
+
+    #[test]
+    fn rejected_record_leaves_state_untouched() {
+        let mut exporter =
+            ULogPollExporter::<_, WideMessages, 64, MI, 64, 48>::new(SlowSink::default(), 100);
+        while !exporter.is_ready() {
+            exporter.poll().unwrap();
+        }
+        let stats = *exporter.stats();
+
+        // 'A' fits the pending buffer but 'A' followed by 'D' does not.
+        let data = Record::new_data(0, 0, 1, &[0; 40]).unwrap();
+        assert_eq!(exporter.accept(&data), Err(ExportError::MessageTooLarge));
+        assert!(exporter.pending().is_empty());
+        assert_eq!(*exporter.stats(), stats);
+    }
+
+    #[test]
+    fn oversized_message_is_rejected_without_partial_bytes() {
+        let mut exporter =
+            ULogPollExporter::<_, TestMessages, 64, MI, 64, 64>::new(SlowSink::default(), 100);
+        while !exporter.is_ready() {
+            exporter.poll().unwrap();
+        }
+
+        let log = Record::new_log(LogLevel::Info, None, 1, &[b'x'; 60]);
+        assert_eq!(exporter.accept(&log), Err(ExportError::MessageTooLarge));
+        assert!(exporter.pending().is_empty());
+        assert!(exporter.is_ready());
+    }
+}
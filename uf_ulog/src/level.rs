@@ -0,0 +1,88 @@
+use crate::LogLevel;
+
+// Levels above this are stripped at compile time by the `max_level_*` features;
+// `None` (`max_level_off`) disables logged strings entirely. Features are
+// additive, so the most permissive enabled feature wins. Only the frontends
+// that format text apply it; exporters pass every record to `LevelFilter`.
+pub const STATIC_MAX_LEVEL: Option<LogLevel> = if cfg!(feature = "max_level_debug") {
+    Some(LogLevel::Debug)
+} else if cfg!(feature = "max_level_info") {
+    Some(LogLevel::Info)
+} else if cfg!(feature = "max_level_notice") {
+    Some(LogLevel::Notice)
+} else if cfg!(feature = "max_level_warning") {
+    Some(LogLevel::Warning)
+} else if cfg!(feature = "max_level_err") {
+    Some(LogLevel::Err)
+} else if cfg!(feature = "max_level_off") {
+    None
+} else {
+    Some(LogLevel::Debug)
+};
+
+#[inline]
+pub const fn static_level_enabled(level: LogLevel) -> bool {
+    match STATIC_MAX_LEVEL {
+        Some(max) => level as u8 <= max as u8,
+        None => false,
+    }
+}
+
+// A level passes when it is at least as severe as the threshold for its tag.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LevelFilter {
+    min_level: LogLevel,
+    tag_levels: &'static [(u16, LogLevel)],
+}
+
+impl LevelFilter {
+    pub const fn new(min_level: LogLevel) -> Self {
+        Self {
+            min_level,
+            tag_levels: &[],
+        }
+    }
+
+    pub const fn with_tag_levels(mut self, tag_levels: &'static [(u16, LogLevel)]) -> Self {
+        self.tag_levels = tag_levels;
+        self
+    }
+
+    pub const fn min_level(&self) -> LogLevel {
+        self.min_level
+    }
+
+    #[inline]
+    pub fn allows(&self, level: LogLevel, tag: Option<u16>) -> bool {
+        let threshold = tag
+            .and_then(|tag| {
+                self.tag_levels
+                    .iter()
+                    .find(|(tag_id, _)| *tag_id == tag)
+                    .map(|&(_, level)| level)
+            })
+            .unwrap_or(self.min_level);
+        level as u8 <= threshold as u8
+    }
+}
+
+impl Default for LevelFilter {
+    fn default() -> Self {
+        Self::new(LogLevel::Debug)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tag_overrides_take_precedence() {
+        let filter = LevelFilter::new(LogLevel::Info).with_tag_levels(&[(3, LogLevel::Debug)]);
+
+        assert!(filter.allows(LogLevel::Warning, None));
+        assert!(!filter.allows(LogLevel::Debug, None));
+        assert!(!filter.allows(LogLevel::Debug, Some(2)));
+        assert!(filter.allows(LogLevel::Debug, Some(3)));
+    }
+}
@@ -6,22 +6,123 @@
 extern crate self as uf_ulog;
 
 #[cfg(feature = "derive")]
-pub use uf_ulog_macro::{ULogData, ULogRegistry};
+pub use uf_ulog_macro::{ULogData, ULogNested, ULogRegistry};
+mod clock;
 mod data;
+#[cfg(feature = "defmt")]
+mod defmt_logger;
+mod encode;
 mod exporter;
 #[cfg(feature = "async")]
 mod exporter_async;
+mod exporter_poll;
+mod level;
+#[cfg(feature = "log")]
+mod logger;
+mod macros;
 mod producer;
+mod profile;
+mod queue;
+mod reader;
 mod registry;
+mod ring;
+#[cfg(any(feature = "log", feature = "tracing"))]
+mod sink;
+mod stats;
+#[cfg(feature = "tracing")]
+mod tracing_layer;
 mod wire;
 
+pub use clock::Clock;
+#[cfg(feature = "embassy-time")]
+pub use clock::EmbassyClock;
+#[cfg(feature = "std")]
+pub use clock::StdClock;
 pub use data::{
-    EncodeError, LogLevel, LoggedString, ParameterValue, Record, RecordKind, RecordMeta,
-    Subscription, TrySendError, ULogData,
+    fixed_str, DecodeError, DefaultTypes, EncodeError, InfoValue, LogLevel, LoggedString,
+    ParameterValue, Record, RecordKind, RecordMeta, Subscription, TrySendError, ULogData,
+    ULogNested,
 };
+pub use encode::{encode_add_subscription, encode_data, encode_record, encode_startup};
 pub use exporter::{FormatsPending, StreamingReady, ULogCoreExporter};
 #[cfg(feature = "async")]
 pub use exporter_async::ULogAsyncCoreExporter;
-pub use producer::{BuildError, ULogProducer};
-pub use registry::{MessageMeta, Registry, Topic, TopicOf, ULogRegistry};
-pub use wire::{ExportError, ExportStep};
+pub use exporter_poll::ULogPollExporter;
+pub use level::{LevelFilter, STATIC_MAX_LEVEL};
+#[cfg(feature = "log")]
+pub use logger::ULogLogger;
+pub use producer::{BuildError, MultiInfoChunks, NoClock, ULogProducer};
+pub use profile::LogProfile;
+pub use queue::{RecordQueue, RecordReceiver, RecordSender};
+pub use reader::{ReadError, ULogEvent, ULogReader};
+pub use registry::{
+    MessageMeta, NestedFormats, NestedMeta, Registry, Topic, TopicOf, ULogRegistry,
+};
+pub use ring::{RecordRing, RingGrant, RingReader, RingWriter};
+#[cfg(any(feature = "log", feature = "tracing"))]
+pub use sink::RecordSink;
+pub use stats::{ExportStats, ExportStatsData, StreamStats, StreamStatsData};
+#[cfg(feature = "tracing")]
+pub use tracing_layer::ULogLayer;
+pub use wire::{ExportError, ExportStep, FlagBits, SyncPolicy};
+
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "defmt")]
+    pub use crate::defmt_logger::DefmtFrame;
+    pub use crate::level::static_level_enabled;
+    #[cfg(feature = "defmt")]
+    pub use critical_section;
+    #[cfg(feature = "defmt")]
+    pub use defmt;
+
+    pub const fn concat_len(parts: &[&str]) -> usize {
+        let mut len = 0;
+        let mut i = 0;
+        while i < parts.len() {
+            len += parts[i].len();
+            i += 1;
+        }
+        len
+    }
+
+    pub const fn concat<const N: usize>(parts: &[&str]) -> [u8; N] {
+        let mut out = [0u8; N];
+        let mut offset = 0;
+        let mut i = 0;
+        while i < parts.len() {
+            let bytes = parts[i].as_bytes();
+            let mut j = 0;
+            while j < bytes.len() {
+                out[offset] = bytes[j];
+                offset += 1;
+                j += 1;
+            }
+            i += 1;
+        }
+        assert!(offset == N, "concatenated format length mismatch");
+        out
+    }
+
+    pub fn decode_nested_array<T: crate::ULogNested, const N: usize>(
+        buf: &[u8],
+    ) -> Result<[T; N], crate::DecodeError> {
+        let mut values = heapless::Vec::<T, N>::new();
+        for index in 0..N {
+            let bytes = buf
+                .get(index * T::WIRE_SIZE..)
+                .ok_or(crate::DecodeError::BufferTooShort)?;
+            let _ = values.push(T::decode(bytes)?);
+        }
+        values
+            .into_array()
+            .map_err(|_| crate::DecodeError::BufferTooShort)
+    }
+
+    pub const fn as_str(bytes: &'static [u8]) -> &'static str {
+        match core::str::from_utf8(bytes) {
+            Ok(text) => text,
+            Err(_) => panic!("format is not valid UTF-8"),
+        }
+    }
+}
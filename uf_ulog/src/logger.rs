@@ -0,0 +1,197 @@
+use crate::level::static_level_enabled;
+use crate::producer::format_text;
+use crate::{Clock, LevelFilter, LogLevel, Record, RecordSink};
+
+pub struct ULogLogger<S, C, const RECORD_CAP: usize = 128> {
+    sink: S,
+    clock: C,
+    tags: &'static [(&'static str, u16)],
+    level_filter: LevelFilter,
+}
+
+impl<S, C, const RECORD_CAP: usize> ULogLogger<S, C, RECORD_CAP>
+where
+    S: RecordSink<RECORD_CAP>,
+    C: Clock,
+{
+    pub const fn new(sink: S, clock: C) -> Self {
+        Self {
+            sink,
+            clock,
+            tags: &[],
+            level_filter: LevelFilter::new(LogLevel::Debug),
+        }
+    }
+
+    pub const fn with_tags(mut self, tags: &'static [(&'static str, u16)]) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    pub const fn with_level_filter(mut self, level_filter: LevelFilter) -> Self {
+        self.level_filter = level_filter;
+        self
+    }
+
+    pub fn sink(&self) -> &S {
+        &self.sink
+    }
+
+    fn tag_for(&self, target: &str) -> Option<u16> {
+        // Longest matching module prefix wins, so `nav::ekf` can override `nav`.
+        self.tags
+            .iter()
+            .filter(|(prefix, _)| {
+                target
+                    .strip_prefix(prefix)
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+            })
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|&(_, tag)| tag)
+    }
+
+    fn allows(&self, level: LogLevel, tag: Option<u16>) -> bool {
+        static_level_enabled(level) && self.level_filter.allows(level, tag)
+    }
+}
+
+impl<S, C, const RECORD_CAP: usize> log::Log for ULogLogger<S, C, RECORD_CAP>
+where
+    S: RecordSink<RECORD_CAP> + Send + Sync,
+    C: Clock + Send + Sync,
+{
+    fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+        self.allows(log_level(metadata.level()), self.tag_for(metadata.target()))
+    }
+
+    fn log(&self, record: &log::Record<'_>) {
+        let level = log_level(record.level());
+        let tag = self.tag_for(record.target());
+        if !self.allows(level, tag) {
+            return;
+        }
+        let text = format_text::<RECORD_CAP>(*record.args());
+        let ts = self.clock.now_micros();
+        let record = Record::new_log(level, tag, ts, &text);
+        // Overruns are counted by the sink; logging must never block or fail.
+        let _ = self.sink.try_send(record);
+    }
+
+    fn flush(&self) {}
+}
+
+fn log_level(level: log::Level) -> LogLevel {
+    match level {
+        log::Level::Error => LogLevel::Err,
+        log::Level::Warn => LogLevel::Warning,
+        log::Level::Info => LogLevel::Info,
+        log::Level::Debug | log::Level::Trace => LogLevel::Debug,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RecordMeta, RecordQueue};
+    use log::Log;
+    use std::sync::Mutex;
+
+    const CAP: usize = 32;
+
+    fn clock() -> u64 {
+        42
+    }
+
+    #[test]
+    fn maps_levels_and_target_tags() {
+        let mut queue = RecordQueue::<CAP, 4>::new();
+        let (tx, mut rx) = queue.split();
+        let logger = ULogLogger::<_, _, CAP>::new(Mutex::new(tx), clock)
+            .with_tags(&[("nav", 1), ("nav::ekf", 2)]);
+
+        for (target, level) in [
+            ("nav::ekf::update", log::Level::Warn),
+            ("nav", log::Level::Trace),
+            ("navigation", log::Level::Error),
+        ] {
+            logger.log(
+                &log::Record::builder()
+                    .args(format_args!("{target}"))
+                    .level(level)
+                    .target(target)
+                    .build(),
+            );
+        }
+
+        let metas = [
+            (LogLevel::Warning, Some(2)),
+            (LogLevel::Debug, Some(1)),
+            (LogLevel::Err, None),
+        ];
+        for (level, tag) in metas
+            .into_iter()
+            .filter(|&(level, _)| static_level_enabled(level))
+        {
+            let record = rx.try_recv().unwrap();
+            assert_eq!(
+                record.meta(),
+                RecordMeta::LoggedString { level, tag, ts: 42 }
+            );
+        }
+        assert!(rx.is_empty());
+    }
+
+    #[test]
+    fn level_filter_applies_per_target_tag() {
+        let mut queue = RecordQueue::<CAP, 4>::new();
+        let (tx, mut rx) = queue.split();
+        let logger = ULogLogger::<_, _, CAP>::new(Mutex::new(tx), clock)
+            .with_tags(&[("nav", 1)])
+            .with_level_filter(
+                LevelFilter::new(LogLevel::Warning).with_tag_levels(&[(1, LogLevel::Info)]),
+            );
+        let metadata = |target| {
+            log::Metadata::builder()
+                .level(log::Level::Info)
+                .target(target)
+                .build()
+        };
+
+        assert!(!logger.enabled(&metadata("motor")));
+        assert_eq!(
+            logger.enabled(&metadata("nav")),
+            static_level_enabled(LogLevel::Info)
+        );
+        for target in ["motor", "nav"] {
+            logger.log(
+                &log::Record::builder()
+                    .args(format_args!("{target}"))
+                    .level(log::Level::Info)
+                    .target(target)
+                    .build(),
+            );
+        }
+
+        if static_level_enabled(LogLevel::Info) {
+            assert_eq!(rx.try_recv().unwrap().bytes(), b"nav");
+        }
+        assert!(rx.is_empty());
+    }
+
+    #[test]
+    fn full_sink_drops_records() {
+        let (tx, rx) = std::sync::mpsc::sync_channel::<Record<CAP>>(1);
+        let logger = ULogLogger::<_, _, CAP>::new(tx, clock);
+        for _ in 0..2 {
+            logger.log(
+                &log::Record::builder()
+                    .args(format_args!("armed"))
+                    .level(log::Level::Error)
+                    .build(),
+            );
+        }
+
+        assert_eq!(rx.try_recv().unwrap().bytes(), b"armed");
+        assert!(rx.try_recv().is_err());
+    }
+}
@@ -0,0 +1,220 @@
+// Returns `None` without formatting when the level is filtered out at compile
+// time or by the producer's `LevelFilter`. The compile-time check comes first
+// so constant levels above `STATIC_MAX_LEVEL` fold away entirely.
+#[macro_export]
+macro_rules! ulog_log {
+    ($producer:expr, $level:expr, tag = $tag:expr, $ts:expr, $($arg:tt)+) => {{
+        let level: $crate::LogLevel = $level;
+        let producer = &$producer;
+        let tag = $tag;
+        if $crate::__private::static_level_enabled(level)
+            && producer.log_enabled(level, ::core::option::Option::Some(tag))
+        {
+            ::core::option::Option::Some(producer.log_tagged_fmt(
+                level,
+                tag,
+                $ts,
+                ::core::format_args!($($arg)+),
+            ))
+        } else {
+            ::core::option::Option::None
+        }
+    }};
+    ($producer:expr, $level:expr, $ts:expr, $($arg:tt)+) => {{
+        let level: $crate::LogLevel = $level;
+        let producer = &$producer;
+        if $crate::__private::static_level_enabled(level)
+            && producer.log_enabled(level, ::core::option::Option::None)
+        {
+            ::core::option::Option::Some(producer.log_fmt(
+                level,
+                $ts,
+                ::core::format_args!($($arg)+),
+            ))
+        } else {
+            ::core::option::Option::None
+        }
+    }};
+}
+
+#[macro_export]
+macro_rules! ulog_emerg {
+    ($producer:expr, $($arg:tt)+) => {
+        $crate::ulog_log!($producer, $crate::LogLevel::Emerg, $($arg)+)
+    };
+}
+
+#[macro_export]
+macro_rules! ulog_alert {
+    ($producer:expr, $($arg:tt)+) => {
+        $crate::ulog_log!($producer, $crate::LogLevel::Alert, $($arg)+)
+    };
+}
+
+#[macro_export]
+macro_rules! ulog_crit {
+    ($producer:expr, $($arg:tt)+) => {
+        $crate::ulog_log!($producer, $crate::LogLevel::Crit, $($arg)+)
+    };
+}
+
+#[macro_export]
+macro_rules! ulog_error {
+    ($producer:expr, $($arg:tt)+) => {
+        $crate::ulog_log!($producer, $crate::LogLevel::Err, $($arg)+)
+    };
+}
+
+#[macro_export]
+macro_rules! ulog_warn {
+    ($producer:expr, $($arg:tt)+) => {
+        $crate::ulog_log!($producer, $crate::LogLevel::Warning, $($arg)+)
+    };
+}
+
+#[macro_export]
+macro_rules! ulog_notice {
+    ($producer:expr, $($arg:tt)+) => {
+        $crate::ulog_log!($producer, $crate::LogLevel::Notice, $($arg)+)
+    };
+}
+
+#[macro_export]
+macro_rules! ulog_info {
+    ($producer:expr, $($arg:tt)+) => {
+        $crate::ulog_log!($producer, $crate::LogLevel::Info, $($arg)+)
+    };
+}
+
+#[macro_export]
+macro_rules! ulog_debug {
+    ($producer:expr, $($arg:tt)+) => {
+        $crate::ulog_log!($producer, $crate::LogLevel::Debug, $($arg)+)
+    };
+}
+
+// defmt frames only carry interned format ids, so the text cannot be recovered
+// from a global logger. These macros log the same literal through both paths
+// instead; only `{}` placeholders are understood by both formatters, and every
+// argument must implement both `Display` and `defmt::Format`.
+//
+// defmt's macros expand to plain `defmt::...` paths, so the re-export is
+// brought into scope as `defmt` and callers do not need their own dependency.
+#[cfg(feature = "defmt")]
+#[macro_export]
+macro_rules! ulog_defmt {
+    ($producer:expr, $level:ident, $ulog_level:expr, $tag:expr, $ts:expr, $fmt:literal $(, $arg:expr)* $(,)?) => {{
+        {
+            use $crate::__private::defmt;
+            defmt::$level!($fmt $(, $arg)*);
+        }
+        $crate::ulog_log!($producer, $ulog_level, tag = $tag, $ts, $fmt $(, $arg)*)
+    }};
+}
+
+#[cfg(feature = "defmt")]
+#[macro_export]
+macro_rules! ulog_defmt_error {
+    ($producer:expr, $tag:expr, $ts:expr, $($arg:tt)+) => {
+        $crate::ulog_defmt!($producer, error, $crate::LogLevel::Err, $tag, $ts, $($arg)+)
+    };
+}
+
+#[cfg(feature = "defmt")]
+#[macro_export]
+macro_rules! ulog_defmt_warn {
+    ($producer:expr, $tag:expr, $ts:expr, $($arg:tt)+) => {
+        $crate::ulog_defmt!($producer, warn, $crate::LogLevel::Warning, $tag, $ts, $($arg)+)
+    };
+}
+
+#[cfg(feature = "defmt")]
+#[macro_export]
+macro_rules! ulog_defmt_info {
+    ($producer:expr, $tag:expr, $ts:expr, $($arg:tt)+) => {
+        $crate::ulog_defmt!($producer, info, $crate::LogLevel::Info, $tag, $ts, $($arg)+)
+    };
+}
+
+#[cfg(feature = "defmt")]
+#[macro_export]
+macro_rules! ulog_defmt_debug {
+    ($producer:expr, $tag:expr, $ts:expr, $($arg:tt)+) => {
+        $crate::ulog_defmt!($producer, debug, $crate::LogLevel::Debug, $tag, $ts, $($arg)+)
+    };
+}
+
+// Defines the `#[defmt::global_logger]` of the binary. Each frame is sent as
+// a `Record` holding the raw (unencoded) frame bytes under `tag`, so flight
+// logs carry the same messages and decode offline with the firmware ELF.
+// With `forward`, the rzCOBS-encoded stream also goes to the probe transport.
+// `send` and `forward` run inside a critical section and must not block.
+#[cfg(feature = "defmt")]
+#[macro_export]
+macro_rules! ulog_defmt_global_logger {
+    (
+        record_cap = $cap:expr,
+        tag = $tag:expr,
+        clock = $clock:expr,
+        send = $send:expr
+        $(, forward = $forward:expr)?
+        $(,)?
+    ) => {
+        const _: () = {
+            use core::cell::RefCell;
+            use core::sync::atomic::{AtomicBool, Ordering};
+            use $crate::__private::critical_section::{self, CriticalSection, Mutex, RestoreState};
+            use $crate::__private::defmt;
+
+            static TAKEN: AtomicBool = AtomicBool::new(false);
+            static mut RESTORE: RestoreState = RestoreState::invalid();
+            static STATE: Mutex<RefCell<($crate::__private::DefmtFrame<{ $cap }>, defmt::Encoder)>> =
+                Mutex::new(RefCell::new(($crate::__private::DefmtFrame::new(), defmt::Encoder::new())));
+
+            fn forward(_bytes: &[u8]) {
+                $(($forward)(_bytes);)?
+            }
+
+            #[defmt::global_logger]
+            struct ULogDefmtLogger;
+
+            unsafe impl defmt::Logger for ULogDefmtLogger {
+                fn acquire() {
+                    let restore = unsafe { critical_section::acquire() };
+                    if TAKEN.swap(true, Ordering::Relaxed) {
+                        panic!("defmt logger taken reentrantly");
+                    }
+                    unsafe { RESTORE = restore };
+                    let cs = unsafe { CriticalSection::new() };
+                    let mut state = STATE.borrow_ref_mut(cs);
+                    state.0.start();
+                    state.1.start_frame(forward);
+                }
+
+                unsafe fn flush() {}
+
+                unsafe fn release() {
+                    let cs = unsafe { CriticalSection::new() };
+                    let record = {
+                        let mut state = STATE.borrow_ref_mut(cs);
+                        state.1.end_frame(forward);
+                        state.0.finish($tag, $crate::Clock::now_micros(&$clock))
+                    };
+                    if let Some(record) = record {
+                        ($send)(record);
+                    }
+                    TAKEN.store(false, Ordering::Relaxed);
+                    let restore = unsafe { RESTORE };
+                    unsafe { critical_section::release(restore) };
+                }
+
+                unsafe fn write(bytes: &[u8]) {
+                    let cs = unsafe { CriticalSection::new() };
+                    let mut state = STATE.borrow_ref_mut(cs);
+                    state.0.write(bytes);
+                    state.1.write(bytes, forward);
+                }
+            }
+        };
+    };
+}
@@ -1,7 +1,12 @@
+use core::cell::Cell;
 use core::marker::PhantomData;
 
-use crate::{EncodeError, LogLevel, ParameterValue, Record, TopicOf, ULogData, ULogRegistry};
+use crate::{
+    Clock, DefaultTypes, EncodeError, InfoValue, LevelFilter, LogLevel, ParameterValue, Record,
+    RecordMeta, RingWriter, TopicOf, ULogData, ULogRegistry,
+};
 
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BuildError {
     InvalidTopicIndex,
@@ -10,27 +15,105 @@ pub enum BuildError {
     Encode(EncodeError),
     RecordTooLarge,
     ParameterNameTooLong,
+    InfoKeyTooLong,
+    NonMonotonicTimestamp,
+    RateLimited,
+    TopicLimitsFull,
 }
 
 pub struct ULogProducer<
     R: ULogRegistry,
     const RECORD_CAP: usize = 128,
     const MAX_MULTI_IDS: usize = 4,
+    C = NoClock,
+    const MAX_TOPIC_LIMITS: usize = 4,
 > {
+    clock: C,
+    topic_limits: heapless::Vec<TopicLimit<MAX_MULTI_IDS>, MAX_TOPIC_LIMITS>,
+    level_filter: LevelFilter,
     _messages: PhantomData<R>,
 }
 
-impl<R, const RECORD_CAP: usize, const MAX_MULTI_IDS: usize>
-    ULogProducer<R, RECORD_CAP, MAX_MULTI_IDS>
+pub struct NoClock;
+
+// Per-topic data checks; `last_ts` is the last built record of each instance.
+struct TopicLimit<const MAX_MULTI_IDS: usize> {
+    topic_index: u16,
+    min_interval_micros: u64,
+    monotonic: bool,
+    last_ts: [Cell<Option<u64>>; MAX_MULTI_IDS],
+}
+
+impl<R, const RECORD_CAP: usize, const MAX_MULTI_IDS: usize, const MAX_TOPIC_LIMITS: usize>
+    ULogProducer<R, RECORD_CAP, MAX_MULTI_IDS, NoClock, MAX_TOPIC_LIMITS>
 where
     R: ULogRegistry,
 {
     pub fn new() -> Self {
         Self {
+            clock: NoClock,
+            topic_limits: heapless::Vec::new(),
+            level_filter: LevelFilter::new(LogLevel::Debug),
             _messages: PhantomData,
         }
     }
 
+    pub fn with_clock<C: Clock>(
+        self,
+        clock: C,
+    ) -> ULogProducer<R, RECORD_CAP, MAX_MULTI_IDS, C, MAX_TOPIC_LIMITS> {
+        ULogProducer {
+            clock,
+            topic_limits: self.topic_limits,
+            level_filter: self.level_filter,
+            _messages: PhantomData,
+        }
+    }
+}
+
+impl<R, const RECORD_CAP: usize, const MAX_MULTI_IDS: usize, C, const MAX_TOPIC_LIMITS: usize>
+    ULogProducer<R, RECORD_CAP, MAX_MULTI_IDS, C, MAX_TOPIC_LIMITS>
+where
+    R: ULogRegistry,
+    C: Clock,
+{
+    pub fn now(&self) -> u64 {
+        self.clock.now_micros()
+    }
+
+    pub fn log_now(&self, level: LogLevel, msg: &str) -> Record<RECORD_CAP> {
+        self.log(level, self.now(), msg)
+    }
+
+    pub fn log_tagged_now(&self, level: LogLevel, tag: u16, msg: &str) -> Record<RECORD_CAP> {
+        self.log_tagged(level, tag, self.now(), msg)
+    }
+
+    pub fn log_fmt_now(
+        &self,
+        level: LogLevel,
+        args: core::fmt::Arguments<'_>,
+    ) -> Record<RECORD_CAP> {
+        self.log_fmt(level, self.now(), args)
+    }
+
+    pub fn log_tagged_fmt_now(
+        &self,
+        level: LogLevel,
+        tag: u16,
+        args: core::fmt::Arguments<'_>,
+    ) -> Record<RECORD_CAP> {
+        self.log_tagged_fmt(level, tag, self.now(), args)
+    }
+}
+
+impl<R, const RECORD_CAP: usize, const MAX_MULTI_IDS: usize, C, const MAX_TOPIC_LIMITS: usize>
+    ULogProducer<R, RECORD_CAP, MAX_MULTI_IDS, C, MAX_TOPIC_LIMITS>
+where
+    R: ULogRegistry,
+{
+    // The `log*` builders never filter; the `try_log*` variants and the
+    // `ulog_*!` macros apply the `LevelFilter` and the `max_level_*` features.
     pub fn log(&self, level: LogLevel, ts: u64, msg: &str) -> Record<RECORD_CAP> {
         let text = make_text::<RECORD_CAP>(msg);
         Record::new_log(level, None, ts, &text)
@@ -41,6 +124,68 @@ where
         Record::new_log(level, Some(tag), ts, &text)
     }
 
+    pub fn log_fmt(
+        &self,
+        level: LogLevel,
+        ts: u64,
+        args: core::fmt::Arguments<'_>,
+    ) -> Record<RECORD_CAP> {
+        let text = format_text::<RECORD_CAP>(args);
+        Record::new_log(level, None, ts, &text)
+    }
+
+    pub fn log_tagged_fmt(
+        &self,
+        level: LogLevel,
+        tag: u16,
+        ts: u64,
+        args: core::fmt::Arguments<'_>,
+    ) -> Record<RECORD_CAP> {
+        let text = format_text::<RECORD_CAP>(args);
+        Record::new_log(level, Some(tag), ts, &text)
+    }
+
+    pub fn try_log(&self, level: LogLevel, ts: u64, msg: &str) -> Option<Record<RECORD_CAP>> {
+        self.log_enabled(level, None)
+            .then(|| self.log(level, ts, msg))
+    }
+
+    pub fn try_log_tagged(
+        &self,
+        level: LogLevel,
+        tag: u16,
+        ts: u64,
+        msg: &str,
+    ) -> Option<Record<RECORD_CAP>> {
+        self.log_enabled(level, Some(tag))
+            .then(|| self.log_tagged(level, tag, ts, msg))
+    }
+
+    pub fn try_log_fmt(
+        &self,
+        level: LogLevel,
+        ts: u64,
+        args: core::fmt::Arguments<'_>,
+    ) -> Option<Record<RECORD_CAP>> {
+        self.log_enabled(level, None)
+            .then(|| self.log_fmt(level, ts, args))
+    }
+
+    pub fn try_log_tagged_fmt(
+        &self,
+        level: LogLevel,
+        tag: u16,
+        ts: u64,
+        args: core::fmt::Arguments<'_>,
+    ) -> Option<Record<RECORD_CAP>> {
+        self.log_enabled(level, Some(tag))
+            .then(|| self.log_tagged_fmt(level, tag, ts, args))
+    }
+
+    pub fn dropout(&self, duration_ms: u16) -> Record<RECORD_CAP> {
+        Record::new_dropout(duration_ms)
+    }
+
     pub fn parameter_i32(&self, name: &str, value: i32) -> Result<Record<RECORD_CAP>, BuildError> {
         self.parameter("int32_t", name, ParameterValue::I32(value))
     }
@@ -49,6 +194,61 @@ where
         self.parameter("float", name, ParameterValue::F32(value))
     }
 
+    pub fn parameter_default_i32(
+        &self,
+        name: &str,
+        value: i32,
+        default_types: DefaultTypes,
+    ) -> Result<Record<RECORD_CAP>, BuildError> {
+        self.parameter_default("int32_t", name, ParameterValue::I32(value), default_types)
+    }
+
+    pub fn parameter_default_f32(
+        &self,
+        name: &str,
+        value: f32,
+        default_types: DefaultTypes,
+    ) -> Result<Record<RECORD_CAP>, BuildError> {
+        self.parameter_default("float", name, ParameterValue::F32(value), default_types)
+    }
+
+    pub fn info(&self, name: &str, value: InfoValue<'_>) -> Result<Record<RECORD_CAP>, BuildError> {
+        let key = value
+            .write_key::<RECORD_CAP>(name)
+            .ok_or(BuildError::InfoKeyTooLong)?;
+        let mut scratch = [0u8; 8];
+        let raw = value.value_bytes(&mut scratch);
+        Record::new_info(key.as_bytes(), raw).ok_or(BuildError::RecordTooLarge)
+    }
+
+    pub fn multi_info(
+        &self,
+        name: &str,
+        value: InfoValue<'_>,
+        is_continued: bool,
+    ) -> Result<Record<RECORD_CAP>, BuildError> {
+        let key = value
+            .write_key::<RECORD_CAP>(name)
+            .ok_or(BuildError::InfoKeyTooLong)?;
+        let mut scratch = [0u8; 8];
+        let raw = value.value_bytes(&mut scratch);
+        Record::new_multi_info(key.as_bytes(), raw, is_continued).ok_or(BuildError::RecordTooLarge)
+    }
+
+    pub fn multi_info_chunks<'a>(
+        &self,
+        name: &'a str,
+        value: &'a str,
+    ) -> MultiInfoChunks<'a, RECORD_CAP> {
+        debug_assert!(value.is_ascii(), "ulog multi info chunks must be ASCII");
+        MultiInfoChunks {
+            name,
+            value: value.as_bytes(),
+            offset: 0,
+            done: false,
+        }
+    }
+
     pub fn data<T>(&self, value: &T) -> Result<Record<RECORD_CAP>, BuildError>
     where
         T: ULogData + TopicOf<R>,
@@ -73,6 +273,9 @@ where
             return Err(BuildError::InvalidMultiId);
         }
 
+        let ts = value.timestamp();
+        self.check_topic_limit(topic_index, instance, ts)?;
+
         let mut encoded = [0; RECORD_CAP];
         let encoded_len = value.encode(&mut encoded).map_err(BuildError::Encode)?;
         if encoded_len != T::WIRE_SIZE {
@@ -82,13 +285,194 @@ where
             return Err(BuildError::RecordTooLarge);
         }
 
-        Record::new_data(
+        let record = Record::new_data(topic_index, instance, ts, &encoded[..encoded_len])
+            .ok_or(BuildError::RecordTooLarge)?;
+        self.commit_topic_limit(topic_index, instance, ts);
+        Ok(record)
+    }
+
+    pub fn data_to_ring<T, const N: usize>(
+        &self,
+        writer: &mut RingWriter<'_, N>,
+        value: &T,
+        instance: u8,
+    ) -> Result<(), BuildError>
+    where
+        T: ULogData + TopicOf<R>,
+    {
+        let topic_index = <T as TopicOf<R>>::TOPIC.id();
+        if usize::from(topic_index) >= R::REGISTRY.len() {
+            return Err(BuildError::InvalidTopicIndex);
+        }
+
+        if usize::from(instance) >= MAX_MULTI_IDS {
+            return Err(BuildError::InvalidMultiId);
+        }
+
+        let ts = value.timestamp();
+        self.check_topic_limit(topic_index, instance, ts)?;
+
+        let meta = RecordMeta::Data {
             topic_index,
             instance,
-            value.timestamp(),
-            &encoded[..encoded_len],
-        )
-        .ok_or(BuildError::RecordTooLarge)
+            ts,
+        };
+        let mut grant = writer
+            .grant(meta, T::WIRE_SIZE)
+            .map_err(|_| BuildError::Encode(EncodeError::QueueFull))?;
+        let encoded_len = value
+            .encode(grant.payload_mut())
+            .map_err(BuildError::Encode)?;
+        if encoded_len != T::WIRE_SIZE {
+            return Err(BuildError::InvalidWireSize);
+        }
+        grant.commit();
+        self.commit_topic_limit(topic_index, instance, ts);
+        Ok(())
+    }
+
+    pub fn set_level_filter(&mut self, level_filter: LevelFilter) {
+        self.level_filter = level_filter;
+    }
+
+    pub fn level_filter(&self) -> LevelFilter {
+        self.level_filter
+    }
+
+    // Checked by the `ulog_*!` macros before formatting.
+    #[inline]
+    pub fn log_enabled(&self, level: LogLevel, tag: Option<u16>) -> bool {
+        crate::level::static_level_enabled(level) && self.level_filter.allows(level, tag)
+    }
+
+    // Data for `T` closer than `min_interval_micros` to the previous record of
+    // the same instance is skipped with `BuildError::RateLimited`.
+    pub fn set_min_interval<T>(&mut self, min_interval_micros: u64) -> Result<(), BuildError>
+    where
+        T: ULogData + TopicOf<R>,
+    {
+        self.topic_limit_mut(<T as TopicOf<R>>::TOPIC.id())?
+            .min_interval_micros = min_interval_micros;
+        Ok(())
+    }
+
+    pub fn clear_min_interval<T>(&mut self)
+    where
+        T: ULogData + TopicOf<R>,
+    {
+        let topic_index = <T as TopicOf<R>>::TOPIC.id();
+        if let Some(limit) = self
+            .topic_limits
+            .iter_mut()
+            .find(|limit| limit.topic_index == topic_index)
+        {
+            limit.min_interval_micros = 0;
+        }
+        self.prune_topic_limits();
+    }
+
+    // Data for `T` older than the previous record of the same instance is
+    // rejected with `BuildError::NonMonotonicTimestamp`.
+    pub fn set_monotonic<T>(&mut self, monotonic: bool) -> Result<(), BuildError>
+    where
+        T: ULogData + TopicOf<R>,
+    {
+        let topic_index = <T as TopicOf<R>>::TOPIC.id();
+        if monotonic {
+            self.topic_limit_mut(topic_index)?.monotonic = true;
+            return Ok(());
+        }
+        if let Some(limit) = self
+            .topic_limits
+            .iter_mut()
+            .find(|limit| limit.topic_index == topic_index)
+        {
+            limit.monotonic = false;
+        }
+        self.prune_topic_limits();
+        Ok(())
+    }
+
+    fn topic_limit_mut(
+        &mut self,
+        topic_index: u16,
+    ) -> Result<&mut TopicLimit<MAX_MULTI_IDS>, BuildError> {
+        let position = self
+            .topic_limits
+            .iter()
+            .position(|limit| limit.topic_index == topic_index);
+        let index = match position {
+            Some(index) => index,
+            None => {
+                self.topic_limits
+                    .push(TopicLimit {
+                        topic_index,
+                        min_interval_micros: 0,
+                        monotonic: false,
+                        last_ts: core::array::from_fn(|_| Cell::new(None)),
+                    })
+                    .map_err(|_| BuildError::TopicLimitsFull)?;
+                self.topic_limits.len() - 1
+            }
+        };
+        Ok(&mut self.topic_limits[index])
+    }
+
+    fn prune_topic_limits(&mut self) {
+        self.topic_limits
+            .retain(|limit| limit.monotonic || limit.min_interval_micros > 0);
+    }
+
+    fn check_topic_limit(&self, topic_index: u16, instance: u8, ts: u64) -> Result<(), BuildError> {
+        let Some((limit, last_ts)) = self.topic_limit(topic_index, instance) else {
+            return Ok(());
+        };
+        let Some(last) = last_ts.get() else {
+            return Ok(());
+        };
+        if limit.monotonic && ts < last {
+            return Err(BuildError::NonMonotonicTimestamp);
+        }
+        if ts.saturating_sub(last) < limit.min_interval_micros {
+            return Err(BuildError::RateLimited);
+        }
+        Ok(())
+    }
+
+    // Only records that were actually built count as the previous record.
+    fn commit_topic_limit(&self, topic_index: u16, instance: u8, ts: u64) {
+        if let Some((_, last_ts)) = self.topic_limit(topic_index, instance) {
+            last_ts.set(Some(ts));
+        }
+    }
+
+    fn topic_limit(
+        &self,
+        topic_index: u16,
+        instance: u8,
+    ) -> Option<(&TopicLimit<MAX_MULTI_IDS>, &Cell<Option<u64>>)> {
+        let limit = self
+            .topic_limits
+            .iter()
+            .find(|limit| limit.topic_index == topic_index)?;
+        let last_ts = limit.last_ts.get(usize::from(instance))?;
+        Some((limit, last_ts))
+    }
+
+    pub fn unsubscribe<T>(&self, instance: u8) -> Result<Record<RECORD_CAP>, BuildError>
+    where
+        T: ULogData + TopicOf<R>,
+    {
+        let topic_index = <T as TopicOf<R>>::TOPIC.id();
+        if usize::from(topic_index) >= R::REGISTRY.len() {
+            return Err(BuildError::InvalidTopicIndex);
+        }
+
+        if usize::from(instance) >= MAX_MULTI_IDS {
+            return Err(BuildError::InvalidMultiId);
+        }
+
+        Ok(Record::new_remove_subscription(topic_index, instance))
     }
 
     fn parameter(
@@ -97,21 +481,25 @@ where
         name: &str,
         value: ParameterValue,
     ) -> Result<Record<RECORD_CAP>, BuildError> {
-        let mut key = heapless::String::<RECORD_CAP>::new();
-        if key.push_str(ty).is_err()
-            || key.push(' ').is_err()
-            || key.push_str(name).is_err()
-            || key.len() > usize::from(u8::MAX)
-        {
-            return Err(BuildError::ParameterNameTooLong);
-        }
-
+        let key = parameter_key::<RECORD_CAP>(ty, name)?;
         Record::new_parameter(key.as_bytes(), value).ok_or(BuildError::RecordTooLarge)
     }
+
+    fn parameter_default(
+        &self,
+        ty: &str,
+        name: &str,
+        value: ParameterValue,
+        default_types: DefaultTypes,
+    ) -> Result<Record<RECORD_CAP>, BuildError> {
+        let key = parameter_key::<RECORD_CAP>(ty, name)?;
+        Record::new_parameter_default(key.as_bytes(), value, default_types)
+            .ok_or(BuildError::RecordTooLarge)
+    }
 }
 
-impl<R, const RECORD_CAP: usize, const MAX_MULTI_IDS: usize> Default
-    for ULogProducer<R, RECORD_CAP, MAX_MULTI_IDS>
+impl<R, const RECORD_CAP: usize, const MAX_MULTI_IDS: usize, const MAX_TOPIC_LIMITS: usize> Default
+    for ULogProducer<R, RECORD_CAP, MAX_MULTI_IDS, NoClock, MAX_TOPIC_LIMITS>
 where
     R: ULogRegistry,
 {
@@ -120,6 +508,71 @@ where
     }
 }
 
+fn parameter_key<const RECORD_CAP: usize>(
+    ty: &str,
+    name: &str,
+) -> Result<heapless::String<RECORD_CAP>, BuildError> {
+    let mut key = heapless::String::<RECORD_CAP>::new();
+    if key.push_str(ty).is_err()
+        || key.push(' ').is_err()
+        || key.push_str(name).is_err()
+        || key.len() > usize::from(u8::MAX)
+    {
+        return Err(BuildError::ParameterNameTooLong);
+    }
+    Ok(key)
+}
+
+pub struct MultiInfoChunks<'a, const RECORD_CAP: usize> {
+    name: &'a str,
+    value: &'a [u8],
+    offset: usize,
+    done: bool,
+}
+
+impl<const RECORD_CAP: usize> Iterator for MultiInfoChunks<'_, RECORD_CAP> {
+    type Item = Result<Record<RECORD_CAP>, BuildError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        // Reserve room for the widest `char[N]` key so every chunk fits.
+        let key_overhead = "char[] ".len() + decimal_digits(RECORD_CAP) + self.name.len();
+        let chunk_cap = RECORD_CAP.saturating_sub(key_overhead);
+        if chunk_cap == 0 {
+            self.done = true;
+            return Some(Err(BuildError::InfoKeyTooLong));
+        }
+
+        let start = self.offset;
+        let end = core::cmp::min(start.saturating_add(chunk_cap), self.value.len());
+        self.offset = end;
+        self.done = end >= self.value.len();
+
+        let chunk = &self.value[start..end];
+        let Some(key) = crate::data::info_key::<RECORD_CAP>("char", Some(chunk.len()), self.name)
+        else {
+            self.done = true;
+            return Some(Err(BuildError::InfoKeyTooLong));
+        };
+        Some(
+            Record::new_multi_info(key.as_bytes(), chunk, start > 0)
+                .ok_or(BuildError::RecordTooLarge),
+        )
+    }
+}
+
+fn decimal_digits(mut value: usize) -> usize {
+    let mut digits = 1;
+    while value >= 10 {
+        value /= 10;
+        digits += 1;
+    }
+    digits
+}
+
 fn make_text<const RECORD_CAP: usize>(msg: &str) -> heapless::Vec<u8, RECORD_CAP> {
     debug_assert!(msg.is_ascii(), "ulog string records must be ASCII");
     let mut text = heapless::Vec::new();
@@ -128,6 +581,53 @@ fn make_text<const RECORD_CAP: usize>(msg: &str) -> heapless::Vec<u8, RECORD_CAP
     text
 }
 
+const TRUNCATION_MARKER: &[u8] = b"...";
+
+pub(crate) struct TextWriter<const RECORD_CAP: usize> {
+    text: heapless::Vec<u8, RECORD_CAP>,
+    truncated: bool,
+}
+
+impl<const RECORD_CAP: usize> TextWriter<RECORD_CAP> {
+    pub(crate) const fn new() -> Self {
+        Self {
+            text: heapless::Vec::new(),
+            truncated: false,
+        }
+    }
+
+    pub(crate) fn finish(mut self) -> heapless::Vec<u8, RECORD_CAP> {
+        if self.truncated {
+            // Overwrite the tail so readers can tell the message was cut short.
+            let start = self.text.len().saturating_sub(TRUNCATION_MARKER.len());
+            let marker_len = self.text.len() - start;
+            self.text[start..].copy_from_slice(&TRUNCATION_MARKER[..marker_len]);
+        }
+        self.text
+    }
+}
+
+impl<const RECORD_CAP: usize> core::fmt::Write for TextWriter<RECORD_CAP> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let room = RECORD_CAP - self.text.len();
+        let end = core::cmp::min(s.len(), room);
+        let _ = self.text.extend_from_slice(&s.as_bytes()[..end]);
+        if end < s.len() {
+            self.truncated = true;
+            return Err(core::fmt::Error);
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn format_text<const RECORD_CAP: usize>(
+    args: core::fmt::Arguments<'_>,
+) -> heapless::Vec<u8, RECORD_CAP> {
+    let mut writer = TextWriter::new();
+    let _ = core::fmt::write(&mut writer, args);
+    writer.finish()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -177,6 +677,179 @@ mod tests {
         assert_eq!(record.kind(), crate::RecordKind::LoggedString);
     }
 
+    #[test]
+    fn log_fmt_formats_without_allocation() {
+        let producer = ULogProducer::<TestMessages, CAP, MI>::new();
+        let record = producer.log_fmt(
+            LogLevel::Info,
+            42,
+            format_args!("gyro {} sat {:.2}", 1, 0.5),
+        );
+        assert_eq!(record.bytes(), b"gyro 1 sat 0.50");
+
+        let record = producer.log_tagged_fmt(LogLevel::Warning, 7, 43, format_args!("x={}", 3));
+        assert_eq!(
+            record.meta(),
+            RecordMeta::LoggedString {
+                level: LogLevel::Warning,
+                tag: Some(7),
+                ts: 43
+            }
+        );
+        assert_eq!(record.bytes(), b"x=3");
+    }
+
+    #[test]
+    fn level_filter_suppresses_macro_logs() {
+        let mut producer = ULogProducer::<TestMessages, CAP, MI>::new();
+        producer.set_level_filter(
+            LevelFilter::new(LogLevel::Info).with_tag_levels(&[(5, LogLevel::Debug)]),
+        );
+
+        // The `max_level_*` features may strip more than the runtime filter.
+        let enabled = crate::__private::static_level_enabled;
+        assert!(crate::ulog_debug!(producer, 1, "noisy {}", 1).is_none());
+        assert_eq!(
+            crate::ulog_debug!(producer, tag = 5, 1, "traced").is_some(),
+            enabled(LogLevel::Debug)
+        );
+        assert_eq!(
+            crate::ulog_info!(producer, 1, "armed").is_some(),
+            enabled(LogLevel::Info)
+        );
+        if let Some(record) = crate::ulog_error!(producer, tag = 7, 2, "x={}", 3) {
+            assert_eq!(record.bytes(), b"x=3");
+        } else {
+            assert!(!enabled(LogLevel::Err));
+        }
+    }
+
+    #[test]
+    fn try_log_applies_level_filter() {
+        let mut producer = ULogProducer::<TestMessages, CAP, MI>::new();
+        producer.set_level_filter(
+            LevelFilter::new(LogLevel::Warning).with_tag_levels(&[(5, LogLevel::Debug)]),
+        );
+        let enabled = crate::__private::static_level_enabled;
+
+        assert!(producer.try_log(LogLevel::Info, 1, "armed").is_none());
+        assert!(producer
+            .try_log_fmt(LogLevel::Debug, 1, format_args!("x={}", 1))
+            .is_none());
+        assert_eq!(
+            producer.try_log_tagged(LogLevel::Debug, 5, 1, "traced"),
+            enabled(LogLevel::Debug).then(|| producer.log_tagged(LogLevel::Debug, 5, 1, "traced"))
+        );
+        assert_eq!(
+            producer
+                .try_log_tagged_fmt(LogLevel::Err, 7, 2, format_args!("x={}", 3))
+                .map(|record| record.bytes().to_vec()),
+            enabled(LogLevel::Err).then(|| b"x=3".to_vec())
+        );
+    }
+
+    #[test]
+    fn log_fmt_marks_truncation() {
+        let producer = ULogProducer::<TestMessages, CAP, MI>::new();
+        let record = producer.log_fmt(
+            LogLevel::Info,
+            1,
+            format_args!("value {} is far too long", 12345),
+        );
+        assert_eq!(record.bytes(), b"value 12345 i...");
+    }
+
+    struct Stamped(u64);
+
+    impl ULogData for Stamped {
+        const FORMAT: &'static str = "uint64_t timestamp;";
+        const NAME: &'static str = "sample";
+        const WIRE_SIZE: usize = 8;
+
+        fn encode(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+            buf[..8].copy_from_slice(&self.0.to_le_bytes());
+            Ok(8)
+        }
+
+        fn timestamp(&self) -> u64 {
+            self.0
+        }
+    }
+
+    impl crate::TopicOf<TestMessages> for Stamped {
+        const TOPIC: crate::Topic<Self> = crate::Topic::new(0);
+    }
+
+    #[test]
+    fn clocked_producer_stamps_logs() {
+        let producer = ULogProducer::<TestMessages, CAP, MI>::new().with_clock(|| 500);
+        let record = producer.log_tagged_now(LogLevel::Info, 3, "armed");
+        assert_eq!(
+            record.meta(),
+            RecordMeta::LoggedString {
+                level: LogLevel::Info,
+                tag: Some(3),
+                ts: 500
+            }
+        );
+    }
+
+    #[test]
+    fn monotonic_topics_reject_older_data_per_instance() {
+        let mut producer = ULogProducer::<TestMessages, CAP, MI>::new();
+        producer.data(&Stamped(20)).unwrap();
+        producer.data(&Stamped(19)).unwrap();
+
+        producer.set_monotonic::<Stamped>(true).unwrap();
+        producer.data(&Stamped(20)).unwrap();
+        producer.data(&Stamped(20)).unwrap();
+        assert_eq!(
+            producer.data(&Stamped(19)).err(),
+            Some(BuildError::NonMonotonicTimestamp)
+        );
+        producer.data_instance(&Stamped(5), 1).unwrap();
+        producer.data(&Stamped(21)).unwrap();
+
+        producer.set_monotonic::<Stamped>(false).unwrap();
+        producer.data(&Stamped(19)).unwrap();
+    }
+
+    #[test]
+    fn min_interval_skips_data_per_instance() {
+        let mut producer = ULogProducer::<TestMessages, CAP, MI>::new();
+        producer.set_min_interval::<Stamped>(4_000).unwrap();
+
+        let kept = (0..20_000)
+            .step_by(250)
+            .filter(|&ts| producer.data(&Stamped(ts)).is_ok())
+            .count();
+        assert_eq!(kept, 5);
+        assert!(producer.data_instance(&Stamped(19_750), 1).is_ok());
+        assert_eq!(
+            producer.data(&Stamped(19_750)).err(),
+            Some(BuildError::RateLimited)
+        );
+
+        producer.clear_min_interval::<Stamped>();
+        assert!(producer.data(&Stamped(19_750)).is_ok());
+
+        // A record that could not be queued does not start a new interval.
+        producer.set_min_interval::<Stamped>(4_000).unwrap();
+        let mut ring = crate::RecordRing::<8>::new();
+        let (mut tx, _rx) = ring.split();
+        assert_eq!(
+            producer.data_to_ring(&mut tx, &Stamped(20_000), 0),
+            Err(BuildError::Encode(EncodeError::QueueFull))
+        );
+        assert!(producer.data(&Stamped(20_000)).is_ok());
+
+        let mut small = ULogProducer::<TestMessages, CAP, MI, NoClock, 0>::new();
+        assert_eq!(
+            small.set_min_interval::<Stamped>(1),
+            Err(BuildError::TopicLimitsFull)
+        );
+    }
+
     #[test]
     fn rejects_too_large_instance() {
         let producer = ULogProducer::<TestMessages, CAP, 1>::new();
@@ -186,6 +859,48 @@ mod tests {
         assert_eq!(status, Err(BuildError::InvalidMultiId));
     }
 
+    #[test]
+    fn data_to_ring_encodes_in_place() {
+        let producer = ULogProducer::<TestMessages, CAP, MI>::new();
+        let mut ring = crate::RecordRing::<32>::new();
+        let (mut tx, mut rx) = ring.split();
+
+        producer.data_to_ring(&mut tx, &SampleData, 2).unwrap();
+        assert_eq!(
+            producer.data_to_ring(&mut tx, &SampleData, 2),
+            Err(BuildError::Encode(EncodeError::QueueFull))
+        );
+
+        let (meta, bytes) = rx.peek().unwrap();
+        assert_eq!(
+            meta,
+            RecordMeta::Data {
+                topic_index: 0,
+                instance: 2,
+                ts: 100
+            }
+        );
+        assert_eq!(bytes, 100u64.to_le_bytes());
+    }
+
+    #[test]
+    fn builds_unsubscribe_record() {
+        let producer = ULogProducer::<TestMessages, CAP, MI>::new();
+        let rec = producer.unsubscribe::<SampleData>(2).unwrap();
+
+        assert_eq!(
+            rec.meta(),
+            crate::RecordMeta::RemoveSubscription {
+                topic_index: 0,
+                instance: 2
+            }
+        );
+        assert_eq!(
+            producer.unsubscribe::<SampleData>(8),
+            Err(BuildError::InvalidMultiId)
+        );
+    }
+
     #[test]
     fn writes_parameter() {
         let producer = ULogProducer::<TestMessages, CAP, MI>::new();
@@ -194,6 +909,83 @@ mod tests {
         assert_eq!(rec.kind(), crate::RecordKind::Parameter);
     }
 
+    #[test]
+    fn writes_parameter_default() {
+        let producer = ULogProducer::<TestMessages, CAP, MI>::new();
+        let defaults = DefaultTypes::SYSTEM | DefaultTypes::CONFIGURATION;
+        let rec = producer.parameter_default_f32("P", 0.5, defaults).unwrap();
+
+        assert_eq!(rec.kind(), crate::RecordKind::ParameterDefault);
+        assert_eq!(
+            rec.meta(),
+            crate::RecordMeta::ParameterDefault {
+                value: ParameterValue::F32(0.5),
+                default_types: defaults,
+            }
+        );
+        assert_eq!(rec.bytes(), b"float P");
+    }
+
+    #[test]
+    fn builds_info_record() {
+        let producer = ULogProducer::<TestMessages, 32, MI>::new();
+        let rec = producer.info("sys_name", InfoValue::Str("uf")).unwrap();
+
+        assert_eq!(rec.kind(), crate::RecordKind::Info);
+        assert_eq!(rec.meta(), crate::RecordMeta::Info { key_len: 16 });
+        assert_eq!(rec.bytes(), b"char[2] sys_nameuf");
+    }
+
+    #[test]
+    fn info_too_large_is_rejected() {
+        let producer = ULogProducer::<TestMessages, CAP, MI>::new();
+
+        assert_eq!(
+            producer.info("time_ref_utc", InfoValue::I32(0)),
+            Err(BuildError::InfoKeyTooLong)
+        );
+        assert_eq!(
+            producer.info("ver", InfoValue::Str("v1.2.3-rc1")),
+            Err(BuildError::RecordTooLarge)
+        );
+    }
+
+    #[test]
+    fn multi_info_chunks_split_long_values() {
+        let producer = ULogProducer::<TestMessages, 24, MI>::new();
+        let records: Vec<_> = producer
+            .multi_info_chunks("boot", "0123456789abcdefghij")
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            records[0].meta(),
+            crate::RecordMeta::MultiInfo {
+                key_len: 13,
+                is_continued: false
+            }
+        );
+        assert_eq!(records[0].bytes(), b"char[11] boot0123456789a");
+        assert_eq!(
+            records[1].meta(),
+            crate::RecordMeta::MultiInfo {
+                key_len: 12,
+                is_continued: true
+            }
+        );
+        assert_eq!(records[1].bytes(), b"char[9] bootbcdefghij");
+    }
+
+    #[test]
+    fn multi_info_chunks_reject_long_name() {
+        let producer = ULogProducer::<TestMessages, CAP, MI>::new();
+        let mut chunks = producer.multi_info_chunks("perf_counter_preflight", "x");
+
+        assert_eq!(chunks.next(), Some(Err(BuildError::InfoKeyTooLong)));
+        assert_eq!(chunks.next(), None);
+    }
+
     #[derive(Default)]
     struct BadSize;
 
@@ -0,0 +1,103 @@
+use crate::TopicOf;
+
+// Selects which registry topics are logged, like PX4's `SDLOG_PROFILE`.
+// Only the first 128 topic indices can be toggled; later ones are always on,
+// and toggling them panics (at compile time in const context).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogProfile {
+    name: &'static str,
+    mask: u128,
+}
+
+impl LogProfile {
+    pub const fn all(name: &'static str) -> Self {
+        Self {
+            name,
+            mask: u128::MAX,
+        }
+    }
+
+    pub const fn none(name: &'static str) -> Self {
+        Self { name, mask: 0 }
+    }
+
+    pub const fn enable<R, T: TopicOf<R>>(self) -> Self {
+        self.with_topic(T::TOPIC.id(), true)
+    }
+
+    pub const fn disable<R, T: TopicOf<R>>(self) -> Self {
+        self.with_topic(T::TOPIC.id(), false)
+    }
+
+    pub const fn with_topic(mut self, topic_index: u16, enabled: bool) -> Self {
+        assert!(
+            topic_index < u128::BITS as u16,
+            "LogProfile covers topic indices below 128"
+        );
+        let bit = 1u128 << topic_index;
+        if enabled {
+            self.mask |= bit;
+        } else {
+            self.mask &= !bit;
+        }
+        self
+    }
+
+    pub const fn is_enabled(&self, topic_index: u16) -> bool {
+        topic_index >= u128::BITS as u16 || self.mask & (1u128 << topic_index) != 0
+    }
+
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Topic;
+
+    enum Messages {}
+
+    struct Gyro;
+    struct Acc;
+    struct Late;
+
+    impl TopicOf<Messages> for Gyro {
+        const TOPIC: Topic<Self> = Topic::new(0);
+    }
+
+    impl TopicOf<Messages> for Acc {
+        const TOPIC: Topic<Self> = Topic::new(127);
+    }
+
+    impl TopicOf<Messages> for Late {
+        const TOPIC: Topic<Self> = Topic::new(128);
+    }
+
+    const REPLAY: LogProfile = LogProfile::none("replay").enable::<Messages, Acc>();
+
+    #[test]
+    fn enable_and_disable_toggle_topics() {
+        assert!(!REPLAY.is_enabled(0));
+        assert!(REPLAY.is_enabled(127));
+        assert_eq!(REPLAY.name(), "replay");
+
+        let quiet = LogProfile::all("quiet").disable::<Messages, Gyro>();
+        assert!(!quiet.is_enabled(0));
+        assert!(quiet.is_enabled(1));
+        assert!(quiet.enable::<Messages, Gyro>().is_enabled(0));
+    }
+
+    #[test]
+    fn topics_past_the_mask_are_always_enabled() {
+        assert!(LogProfile::none("none").is_enabled(128));
+        assert!(LogProfile::none("none").is_enabled(u16::MAX));
+    }
+
+    #[test]
+    #[should_panic(expected = "below 128")]
+    fn toggling_topics_past_the_mask_panics() {
+        let _ = LogProfile::all("all").disable::<Messages, Late>();
+    }
+}
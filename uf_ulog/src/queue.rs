@@ -0,0 +1,120 @@
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use heapless::spsc::{Consumer, Producer, Queue};
+
+use crate::{Record, TrySendError};
+
+pub struct RecordQueue<const RECORD_CAP: usize, const N: usize> {
+    queue: Queue<Record<RECORD_CAP>, N>,
+    overruns: AtomicU32,
+}
+
+impl<const RECORD_CAP: usize, const N: usize> RecordQueue<RECORD_CAP, N> {
+    pub const fn new() -> Self {
+        Self {
+            queue: Queue::new(),
+            overruns: AtomicU32::new(0),
+        }
+    }
+
+    pub fn split(&mut self) -> (RecordSender<'_, RECORD_CAP>, RecordReceiver<'_, RECORD_CAP>) {
+        let Self { queue, overruns } = self;
+        let (producer, consumer) = queue.split();
+        let overruns = &*overruns;
+        (
+            RecordSender { producer, overruns },
+            RecordReceiver {
+                consumer,
+                overruns,
+                reported: 0,
+            },
+        )
+    }
+}
+
+impl<const RECORD_CAP: usize, const N: usize> Default for RecordQueue<RECORD_CAP, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct RecordSender<'a, const RECORD_CAP: usize> {
+    producer: Producer<'a, Record<RECORD_CAP>>,
+    overruns: &'a AtomicU32,
+}
+
+impl<const RECORD_CAP: usize> RecordSender<'_, RECORD_CAP> {
+    pub fn try_send(&mut self, record: Record<RECORD_CAP>) -> Result<(), TrySendError> {
+        self.producer.enqueue(record).map_err(|_record| {
+            // Only the sender writes the counter, so no read-modify-write atomics are needed.
+            let overruns = self.overruns.load(Ordering::Relaxed);
+            self.overruns
+                .store(overruns.wrapping_add(1), Ordering::Release);
+            TrySendError::Full
+        })
+    }
+
+    pub fn is_full(&self) -> bool {
+        !self.producer.ready()
+    }
+}
+
+pub struct RecordReceiver<'a, const RECORD_CAP: usize> {
+    consumer: Consumer<'a, Record<RECORD_CAP>>,
+    overruns: &'a AtomicU32,
+    reported: u32,
+}
+
+impl<const RECORD_CAP: usize> RecordReceiver<'_, RECORD_CAP> {
+    pub fn try_recv(&mut self) -> Option<Record<RECORD_CAP>> {
+        self.consumer.dequeue()
+    }
+
+    pub fn len(&self) -> usize {
+        self.consumer.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.consumer.ready()
+    }
+
+    pub fn take_overruns(&mut self) -> u32 {
+        let overruns = self.overruns.load(Ordering::Acquire);
+        let new = overruns.wrapping_sub(self.reported);
+        self.reported = overruns;
+        new
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LogLevel;
+
+    #[test]
+    fn try_send_reports_full_and_counts_overruns() {
+        let mut queue = RecordQueue::<16, 3>::new();
+        let (mut tx, mut rx) = queue.split();
+
+        for ts in 0..2 {
+            tx.try_send(Record::new_log(LogLevel::Info, None, ts, b"a"))
+                .unwrap();
+        }
+        assert!(tx.is_full());
+        assert_eq!(
+            tx.try_send(Record::new_log(LogLevel::Info, None, 2, b"a")),
+            Err(TrySendError::Full)
+        );
+        assert_eq!(
+            tx.try_send(Record::new_log(LogLevel::Info, None, 3, b"a")),
+            Err(TrySendError::Full)
+        );
+
+        assert_eq!(rx.len(), 2);
+        assert_eq!(rx.take_overruns(), 2);
+        assert_eq!(rx.take_overruns(), 0);
+        assert!(rx.try_recv().is_some());
+        assert!(rx.try_recv().is_some());
+        assert!(rx.is_empty());
+    }
+}
@@ -0,0 +1,580 @@
+use crate::wire::{self, FlagBits, MessageType};
+use crate::{DefaultTypes, LogLevel, ParameterValue};
+
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadError<ReadErr> {
+    Read(ReadErr),
+    InvalidHeader,
+    UnexpectedEof,
+    // With `with_skip_unknown`, the message did not fit `BUF_CAP`; it was
+    // skipped and reading can go on.
+    MessageTooLarge,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ULogEvent<'a> {
+    Header {
+        version: u8,
+        timestamp: u64,
+    },
+    FlagBits(FlagBits),
+    Format {
+        name: &'a str,
+        format: &'a str,
+    },
+    Info {
+        key: &'a str,
+        value: &'a [u8],
+    },
+    MultiInfo {
+        is_continued: bool,
+        key: &'a str,
+        value: &'a [u8],
+    },
+    Parameter {
+        name: &'a str,
+        value: ParameterValue,
+    },
+    ParameterDefault {
+        default_types: DefaultTypes,
+        name: &'a str,
+        value: ParameterValue,
+    },
+    AddSubscription {
+        multi_id: u8,
+        msg_id: u16,
+        name: &'a str,
+    },
+    RemoveSubscription {
+        msg_id: u16,
+    },
+    Data {
+        msg_id: u16,
+        data: &'a [u8],
+    },
+    LoggedString {
+        level: LogLevel,
+        tag: Option<u16>,
+        timestamp: u64,
+        text: &'a [u8],
+    },
+    Sync,
+    Dropout {
+        duration_ms: u16,
+    },
+    Resynced {
+        skipped: usize,
+    },
+}
+
+pub struct ULogReader<Rd, const BUF_CAP: usize = 512> {
+    reader: Rd,
+    buf: [u8; BUF_CAP],
+    start: usize,
+    end: usize,
+    header_read: bool,
+    resyncing: bool,
+    skip_unknown: bool,
+    eof: bool,
+    skipped: usize,
+}
+
+impl<Rd, const BUF_CAP: usize> ULogReader<Rd, BUF_CAP>
+where
+    Rd: embedded_io::Read,
+{
+    pub fn new(reader: Rd) -> Self {
+        Self {
+            reader,
+            buf: [0; BUF_CAP],
+            start: 0,
+            end: 0,
+            header_read: false,
+            resyncing: false,
+            skip_unknown: false,
+            eof: false,
+            skipped: 0,
+        }
+    }
+
+    // Unknown types and messages over `BUF_CAP` are treated as corruption and
+    // resynchronised by default. Enabling this trusts their length and skips
+    // them instead, for logs from newer writers on a reliable medium.
+    pub fn with_skip_unknown(mut self, skip_unknown: bool) -> Self {
+        self.skip_unknown = skip_unknown;
+        self
+    }
+
+    pub fn reader_mut(&mut self) -> &mut Rd {
+        &mut self.reader
+    }
+
+    pub fn next_event(
+        &mut self,
+    ) -> Result<Option<ULogEvent<'_>>, ReadError<<Rd as embedded_io::ErrorType>::Error>> {
+        if !self.header_read {
+            return self.read_header().map(Some);
+        }
+
+        loop {
+            if self.resyncing {
+                let Some(skipped) = self.find_sync()? else {
+                    return Ok(None);
+                };
+                self.resyncing = false;
+                return Ok(Some(ULogEvent::Resynced { skipped }));
+            }
+
+            if !self.fill_to(wire::MESSAGE_HEADER_LEN)? {
+                if self.start == self.end {
+                    return Ok(None);
+                }
+                return Err(ReadError::UnexpectedEof);
+            }
+
+            let header = &self.buf[self.start..self.start + wire::MESSAGE_HEADER_LEN];
+            let payload_len = usize::from(u16::from_le_bytes([header[0], header[1]]));
+            let total_len = wire::MESSAGE_HEADER_LEN + payload_len;
+            let msg_type = MessageType::from_u8(header[2]);
+            if !self.skip_unknown && (msg_type.is_none() || total_len > BUF_CAP) {
+                self.begin_resync();
+                continue;
+            }
+            let Some(msg_type) = msg_type else {
+                self.skip(total_len)?;
+                continue;
+            };
+            if total_len > BUF_CAP {
+                self.skip(total_len)?;
+                return Err(ReadError::MessageTooLarge);
+            }
+
+            if !self.fill_to(total_len)? {
+                return Err(ReadError::UnexpectedEof);
+            }
+
+            let payload = self.start + wire::MESSAGE_HEADER_LEN..self.start + total_len;
+            // Validate first: returning a borrow of `buf` from inside the loop
+            // would otherwise keep `self` borrowed on the resync path.
+            if parse_event(msg_type, &self.buf[payload.clone()]).is_none() {
+                self.begin_resync();
+                continue;
+            }
+
+            self.start += total_len;
+            return Ok(parse_event(msg_type, &self.buf[payload]));
+        }
+    }
+
+    fn read_header(
+        &mut self,
+    ) -> Result<ULogEvent<'static>, ReadError<<Rd as embedded_io::ErrorType>::Error>> {
+        if !self.fill_to(wire::ULOG_HEADER_LEN)? {
+            return Err(ReadError::InvalidHeader);
+        }
+
+        let header = &self.buf[self.start..self.start + wire::ULOG_HEADER_LEN];
+        // The last magic byte is the file format version.
+        if header[..7] != wire::ULOG_HEADER_MAGIC[..7] {
+            return Err(ReadError::InvalidHeader);
+        }
+        let version = header[7];
+        let timestamp = read_u64(header, 8).ok_or(ReadError::InvalidHeader)?;
+
+        self.start += wire::ULOG_HEADER_LEN;
+        self.header_read = true;
+        Ok(ULogEvent::Header { version, timestamp })
+    }
+
+    fn skip(
+        &mut self,
+        mut len: usize,
+    ) -> Result<(), ReadError<<Rd as embedded_io::ErrorType>::Error>> {
+        loop {
+            let buffered = core::cmp::min(len, self.end - self.start);
+            self.start += buffered;
+            len -= buffered;
+            if len == 0 {
+                return Ok(());
+            }
+            if self.eof {
+                return Err(ReadError::UnexpectedEof);
+            }
+            self.read_more()?;
+        }
+    }
+
+    fn begin_resync(&mut self) {
+        self.start += 1;
+        self.skipped = 1;
+        self.resyncing = true;
+    }
+
+    fn find_sync(
+        &mut self,
+    ) -> Result<Option<usize>, ReadError<<Rd as embedded_io::ErrorType>::Error>> {
+        let magic_len = wire::ULOG_SYNC_MAGIC.len();
+        loop {
+            let window = &self.buf[self.start..self.end];
+            if let Some(pos) = window
+                .windows(magic_len)
+                .position(|candidate| candidate == wire::ULOG_SYNC_MAGIC)
+            {
+                self.start += pos + magic_len;
+                let skipped = self.skipped + pos;
+                self.skipped = 0;
+                return Ok(Some(skipped));
+            }
+
+            // Keep a partial magic that may continue in the next read.
+            let keep = core::cmp::min(magic_len - 1, window.len());
+            let dropped = window.len() - keep;
+            self.start += dropped;
+            self.skipped += dropped;
+
+            if self.eof {
+                self.skipped += self.end - self.start;
+                self.start = self.end;
+                return Ok(None);
+            }
+            self.read_more()?;
+        }
+    }
+
+    fn fill_to(
+        &mut self,
+        len: usize,
+    ) -> Result<bool, ReadError<<Rd as embedded_io::ErrorType>::Error>> {
+        while self.end - self.start < len {
+            if self.eof {
+                return Ok(false);
+            }
+            self.read_more()?;
+        }
+        Ok(true)
+    }
+
+    fn read_more(&mut self) -> Result<(), ReadError<<Rd as embedded_io::ErrorType>::Error>> {
+        if self.start > 0 {
+            self.buf.copy_within(self.start..self.end, 0);
+            self.end -= self.start;
+            self.start = 0;
+        }
+
+        let read = self
+            .reader
+            .read(&mut self.buf[self.end..])
+            .map_err(ReadError::Read)?;
+        if read == 0 {
+            self.eof = true;
+        }
+        self.end += read;
+        Ok(())
+    }
+}
+
+fn parse_event(msg_type: MessageType, payload: &[u8]) -> Option<ULogEvent<'_>> {
+    match msg_type {
+        MessageType::FlagBits => FlagBits::decode(payload).map(ULogEvent::FlagBits),
+        MessageType::Format => {
+            let text = core::str::from_utf8(payload).ok()?;
+            let (name, format) = text.split_once(':')?;
+            Some(ULogEvent::Format { name, format })
+        }
+        MessageType::Info => {
+            let (key, value) = split_key(payload)?;
+            Some(ULogEvent::Info { key, value })
+        }
+        MessageType::MultiInfo => {
+            let (&is_continued, rest) = payload.split_first()?;
+            let (key, value) = split_key(rest)?;
+            Some(ULogEvent::MultiInfo {
+                is_continued: is_continued != 0,
+                key,
+                value,
+            })
+        }
+        MessageType::Parameter => {
+            let (key, value) = split_key(payload)?;
+            let (name, value) = ParameterValue::decode(key, value)?;
+            Some(ULogEvent::Parameter { name, value })
+        }
+        MessageType::ParameterDefault => {
+            let (&default_types, rest) = payload.split_first()?;
+            let (key, value) = split_key(rest)?;
+            let (name, value) = ParameterValue::decode(key, value)?;
+            Some(ULogEvent::ParameterDefault {
+                default_types: DefaultTypes::from_bits(default_types),
+                name,
+                value,
+            })
+        }
+        MessageType::AddSubscription => Some(ULogEvent::AddSubscription {
+            multi_id: *payload.first()?,
+            msg_id: read_u16(payload, 1)?,
+            name: core::str::from_utf8(payload.get(3..)?).ok()?,
+        }),
+        MessageType::RemoveSubscription => Some(ULogEvent::RemoveSubscription {
+            msg_id: read_u16(payload, 0)?,
+        }),
+        MessageType::Data => Some(ULogEvent::Data {
+            msg_id: read_u16(payload, 0)?,
+            data: payload.get(2..)?,
+        }),
+        MessageType::LoggedString => Some(ULogEvent::LoggedString {
+            level: LogLevel::try_from(*payload.first()?).ok()?,
+            tag: None,
+            timestamp: read_u64(payload, 1)?,
+            text: payload.get(9..)?,
+        }),
+        MessageType::TaggedLoggedString => Some(ULogEvent::LoggedString {
+            level: LogLevel::try_from(*payload.first()?).ok()?,
+            tag: Some(read_u16(payload, 1)?),
+            timestamp: read_u64(payload, 3)?,
+            text: payload.get(11..)?,
+        }),
+        MessageType::Sync => (payload == wire::ULOG_SYNC_MAGIC).then_some(ULogEvent::Sync),
+        MessageType::Dropout => Some(ULogEvent::Dropout {
+            duration_ms: read_u16(payload, 0)?,
+        }),
+    }
+}
+
+fn split_key(payload: &[u8]) -> Option<(&str, &[u8])> {
+    let (&key_len, rest) = payload.split_first()?;
+    let (key, value) = rest.split_at_checked(usize::from(key_len))?;
+    Some((core::str::from_utf8(key).ok()?, value))
+}
+
+fn read_u16(bytes: &[u8], at: usize) -> Option<u16> {
+    let raw = bytes.get(at..at.checked_add(2)?)?;
+    Some(u16::from_le_bytes(raw.try_into().ok()?))
+}
+
+fn read_u64(bytes: &[u8], at: usize) -> Option<u64> {
+    let raw = bytes.get(at..at.checked_add(8)?)?;
+    Some(u64::from_le_bytes(raw.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exporter::FormatsPending;
+    use crate::{Record, ULogCoreExporter};
+
+    #[derive(Default)]
+    struct VecSink {
+        bytes: std::vec::Vec<u8>,
+    }
+
+    impl embedded_io::ErrorType for VecSink {
+        type Error = core::convert::Infallible;
+    }
+
+    impl embedded_io::Write for VecSink {
+        fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            self.bytes.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    enum TestMessages {}
+
+    impl crate::ULogRegistry for TestMessages {
+        const REGISTRY: crate::Registry = crate::Registry::new(&[crate::MessageMeta {
+            name: "sample",
+            format: "uint64_t timestamp;",
+            wire_size: 8,
+        }]);
+    }
+
+    fn sample_log() -> std::vec::Vec<u8> {
+        let mut exporter =
+            ULogCoreExporter::<_, TestMessages, FormatsPending, 32, 4, 16>::new(VecSink::default())
+                .start(42)
+                .unwrap();
+        exporter
+            .emit_info("sys_name", crate::InfoValue::Str("uf"))
+            .unwrap();
+        exporter
+            .accept(Record::new_parameter(b"int32_t P", ParameterValue::I32(7)).unwrap())
+            .unwrap();
+        exporter
+            .accept(Record::new_data(0, 1, 100, &[1, 2, 3, 4, 5, 6, 7, 8]).unwrap())
+            .unwrap();
+        exporter.emit_sync().unwrap();
+        exporter
+            .accept(Record::new_log(LogLevel::Warning, Some(3), 200, b"hot"))
+            .unwrap();
+        core::mem::take(&mut exporter.writer_mut().bytes)
+    }
+
+    #[test]
+    fn reads_back_exported_log() {
+        let bytes = sample_log();
+        let mut reader = ULogReader::<_, 64>::new(bytes.as_slice());
+
+        assert_eq!(
+            reader.next_event().unwrap(),
+            Some(ULogEvent::Header {
+                version: 1,
+                timestamp: 42
+            })
+        );
+        assert_eq!(
+            reader.next_event().unwrap(),
+            Some(ULogEvent::FlagBits(FlagBits::new()))
+        );
+        assert_eq!(
+            reader.next_event().unwrap(),
+            Some(ULogEvent::Format {
+                name: "sample",
+                format: "uint64_t timestamp;"
+            })
+        );
+        assert_eq!(
+            reader.next_event().unwrap(),
+            Some(ULogEvent::Info {
+                key: "char[2] sys_name",
+                value: b"uf"
+            })
+        );
+        assert_eq!(
+            reader.next_event().unwrap(),
+            Some(ULogEvent::Parameter {
+                name: "P",
+                value: ParameterValue::I32(7)
+            })
+        );
+        assert_eq!(
+            reader.next_event().unwrap(),
+            Some(ULogEvent::AddSubscription {
+                multi_id: 1,
+                msg_id: 1,
+                name: "sample"
+            })
+        );
+        assert_eq!(
+            reader.next_event().unwrap(),
+            Some(ULogEvent::Data {
+                msg_id: 1,
+                data: &[1, 2, 3, 4, 5, 6, 7, 8]
+            })
+        );
+        assert_eq!(reader.next_event().unwrap(), Some(ULogEvent::Sync));
+        assert_eq!(
+            reader.next_event().unwrap(),
+            Some(ULogEvent::LoggedString {
+                level: LogLevel::Warning,
+                tag: Some(3),
+                timestamp: 200,
+                text: b"hot"
+            })
+        );
+        assert_eq!(reader.next_event().unwrap(), None);
+    }
+
+    #[test]
+    fn resyncs_after_corruption() {
+        let mut bytes = sample_log();
+        // Retyped as a log string, the data payload has an invalid level.
+        let data_pos = bytes.windows(3).position(|w| w == [10, 0, b'D']).unwrap();
+        bytes[data_pos + 2] = b'L';
+        let sync_pos = bytes
+            .windows(8)
+            .position(|w| w == wire::ULOG_SYNC_MAGIC)
+            .unwrap();
+
+        let mut reader = ULogReader::<_, 64>::new(bytes.as_slice());
+        let mut events = std::vec::Vec::new();
+        while let Some(event) = reader.next_event().unwrap() {
+            events.push(format!("{event:?}"));
+        }
+
+        assert_eq!(
+            events[6],
+            format!(
+                "{:?}",
+                ULogEvent::Resynced {
+                    skipped: sync_pos - data_pos
+                }
+            )
+        );
+        assert!(events[7].starts_with("LoggedString"));
+        assert_eq!(events.len(), 8);
+    }
+
+    #[test]
+    fn skips_unknown_and_oversized_messages() {
+        let mut bytes = sample_log();
+        let data_pos = bytes.windows(3).position(|w| w == [10, 0, b'D']).unwrap();
+        bytes.splice(data_pos..data_pos, [2, 0, 0xEE, 1, 2]);
+        let mut oversized = std::vec![b'x'; 80];
+        oversized.splice(..3, [77, 0, b'I']);
+        bytes.splice(data_pos..data_pos, oversized);
+
+        let mut reader = ULogReader::<_, 64>::new(bytes.as_slice()).with_skip_unknown(true);
+        for _ in 0..6 {
+            reader.next_event().unwrap();
+        }
+        assert_eq!(reader.next_event(), Err(ReadError::MessageTooLarge));
+        assert!(matches!(
+            reader.next_event().unwrap(),
+            Some(ULogEvent::Data { msg_id: 1, .. })
+        ));
+        assert_eq!(reader.next_event().unwrap(), Some(ULogEvent::Sync));
+    }
+
+    #[test]
+    fn resyncs_on_corrupt_type_or_length() {
+        let clean = sample_log();
+        let data_pos = clean.windows(3).position(|w| w == [10, 0, b'D']).unwrap();
+        let sync_pos = clean
+            .windows(8)
+            .position(|w| w == wire::ULOG_SYNC_MAGIC)
+            .unwrap();
+
+        let mut bad_type = clean.clone();
+        bad_type[data_pos + 2] = 0xEE;
+        let mut bad_len = clean;
+        bad_len[data_pos + 1] = 0xF0;
+
+        for bytes in [bad_type, bad_len] {
+            let mut reader = ULogReader::<_, 64>::new(bytes.as_slice());
+            for _ in 0..6 {
+                reader.next_event().unwrap();
+            }
+            assert_eq!(
+                reader.next_event().unwrap(),
+                Some(ULogEvent::Resynced {
+                    skipped: sync_pos - data_pos
+                })
+            );
+            assert!(matches!(
+                reader.next_event().unwrap(),
+                Some(ULogEvent::LoggedString { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_header_and_truncated_message() {
+        let mut reader = ULogReader::<_, 64>::new(&b"not a ulog file!"[..]);
+        assert_eq!(reader.next_event(), Err(ReadError::InvalidHeader));
+
+        let bytes = sample_log();
+        let mut reader = ULogReader::<_, 64>::new(&bytes[..bytes.len() - 1]);
+        let err = loop {
+            match reader.next_event() {
+                Ok(Some(_)) => {}
+                other => break other,
+            }
+        };
+        assert_eq!(err, Err(ReadError::UnexpectedEof));
+    }
+}
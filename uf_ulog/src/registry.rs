@@ -1,3 +1,5 @@
+use crate::{DecodeError, ULogData};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct MessageMeta {
     pub name: &'static str,
@@ -5,6 +7,13 @@ pub struct MessageMeta {
     pub wire_size: usize,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NestedMeta {
+    pub name: &'static str,
+    pub format: &'static str,
+    pub nested: &'static [NestedMeta],
+}
+
 pub trait ULogRegistry: Sized {
     const REGISTRY: Registry;
 }
@@ -26,6 +35,13 @@ impl<T> Topic<T> {
     pub const fn id(self) -> u16 {
         self.id
     }
+
+    pub fn decode(self, topic_index: u16, bytes: &[u8]) -> Option<Result<T, DecodeError>>
+    where
+        T: ULogData,
+    {
+        (topic_index == self.id).then(|| T::decode(bytes))
+    }
 }
 
 pub trait TopicOf<R>: Sized {
@@ -35,10 +51,18 @@ pub trait TopicOf<R>: Sized {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Registry {
     pub entries: &'static [MessageMeta],
+    nested: &'static [&'static [NestedMeta]],
 }
 
 impl Registry {
     pub const fn new(entries: &'static [MessageMeta]) -> Self {
+        Self::with_nested(entries, &[])
+    }
+
+    pub const fn with_nested(
+        entries: &'static [MessageMeta],
+        nested: &'static [&'static [NestedMeta]],
+    ) -> Self {
         let mut i = 0;
         while i < entries.len() {
             let mut j = i + 1;
@@ -51,7 +75,16 @@ impl Registry {
             }
             i += 1;
         }
-        Self { entries }
+        let mut i = 0;
+        while i < nested.len() {
+            check_nested(entries, nested, nested[i]);
+            i += 1;
+        }
+        Self { entries, nested }
+    }
+
+    pub const fn nested(&self) -> &'static [&'static [NestedMeta]] {
+        self.nested
     }
 
     pub const fn len(&self) -> usize {
@@ -70,6 +103,165 @@ impl Registry {
             Some(&self.entries[index])
         }
     }
+
+    // Longest `name:format` payload among the entries and nested formats.
+    pub const fn max_format_len(&self) -> usize {
+        let mut max = 0;
+        let mut i = 0;
+        while i < self.entries.len() {
+            let len = self.entries[i].name.len() + 1 + self.entries[i].format.len();
+            if len > max {
+                max = len;
+            }
+            i += 1;
+        }
+        let mut i = 0;
+        while i < self.nested.len() {
+            let len = max_nested_format_len(self.nested[i]);
+            if len > max {
+                max = len;
+            }
+            i += 1;
+        }
+        max
+    }
+
+    pub fn nested_formats(&self) -> NestedFormats {
+        NestedFormats {
+            registry: *self,
+            next: 0,
+        }
+    }
+
+    // Nested formats in depth-first order, dependencies before their users.
+    fn find_nested(
+        &self,
+        mut visit: impl FnMut(&'static NestedMeta) -> bool,
+    ) -> Option<&'static NestedMeta> {
+        self.nested
+            .iter()
+            .find_map(|formats| find_nested(formats, &mut visit))
+    }
+
+    pub const fn index_of(&self, name: &str) -> Option<u16> {
+        let mut i = 0;
+        while i < self.entries.len() {
+            if str_eq(self.entries[i].name, name) {
+                #[allow(clippy::cast_possible_truncation)]
+                return Some(i as u16);
+            }
+            i += 1;
+        }
+        None
+    }
+}
+
+const fn max_nested_format_len(formats: &'static [NestedMeta]) -> usize {
+    let mut max = 0;
+    let mut i = 0;
+    while i < formats.len() {
+        let own = formats[i].name.len() + 1 + formats[i].format.len();
+        let inner = max_nested_format_len(formats[i].nested);
+        if own > max {
+            max = own;
+        }
+        if inner > max {
+            max = inner;
+        }
+        i += 1;
+    }
+    max
+}
+
+// A nested name may repeat across messages only with the same format, and
+// may not reuse a message name, so formats can be deduplicated by name.
+const fn check_nested(
+    entries: &'static [MessageMeta],
+    all: &'static [&'static [NestedMeta]],
+    formats: &'static [NestedMeta],
+) {
+    let mut i = 0;
+    while i < formats.len() {
+        let mut j = 0;
+        while j < entries.len() {
+            assert!(
+                !str_eq(entries[j].name, formats[i].name),
+                "nested ULog format reuses a message name"
+            );
+            j += 1;
+        }
+        let mut j = 0;
+        while j < all.len() {
+            assert!(
+                !conflicts(all[j], &formats[i]),
+                "nested ULog format name used with different formats"
+            );
+            j += 1;
+        }
+        check_nested(entries, all, formats[i].nested);
+        i += 1;
+    }
+}
+
+const fn conflicts(formats: &'static [NestedMeta], meta: &NestedMeta) -> bool {
+    let mut i = 0;
+    while i < formats.len() {
+        if str_eq(formats[i].name, meta.name) && !str_eq(formats[i].format, meta.format) {
+            return true;
+        }
+        if conflicts(formats[i].nested, meta) {
+            return true;
+        }
+        i += 1;
+    }
+    false
+}
+
+fn find_nested(
+    formats: &'static [NestedMeta],
+    visit: &mut impl FnMut(&'static NestedMeta) -> bool,
+) -> Option<&'static NestedMeta> {
+    for format in formats {
+        if let Some(found) = find_nested(format.nested, visit) {
+            return Some(found);
+        }
+        if visit(format) {
+            return Some(format);
+        }
+    }
+    None
+}
+
+#[derive(Debug, Clone)]
+pub struct NestedFormats {
+    registry: Registry,
+    next: usize,
+}
+
+impl Iterator for NestedFormats {
+    type Item = &'static NestedMeta;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let index = self.next;
+            let mut position = 0;
+            let node = self.registry.find_nested(|_| {
+                position += 1;
+                position > index
+            })?;
+            self.next += 1;
+            // Only the first occurrence of a name is yielded.
+            let mut first = 0;
+            self.registry.find_nested(|format| {
+                let found = format.name == node.name;
+                first += usize::from(!found);
+                found
+            });
+            if first == index {
+                return Some(node);
+            }
+        }
+    }
 }
 
 const fn str_eq(a: &str, b: &str) -> bool {
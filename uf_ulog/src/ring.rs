@@ -0,0 +1,490 @@
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+use crate::{DefaultTypes, LogLevel, ParameterValue, Record, RecordMeta, TrySendError};
+
+const FRAME_HEADER_LEN: usize = 2;
+const META_MAX_LEN: usize = 12;
+// A zero frame length tells the reader to continue at the start of the buffer.
+const WRAP_MARKER: u16 = 0;
+
+const KIND_LOG: u8 = 0;
+const KIND_TAGGED_LOG: u8 = 1;
+const KIND_DATA: u8 = 2;
+const KIND_PARAMETER: u8 = 3;
+const KIND_PARAMETER_DEFAULT: u8 = 4;
+const KIND_DROPOUT: u8 = 5;
+const KIND_REMOVE_SUBSCRIPTION: u8 = 6;
+const KIND_INFO: u8 = 7;
+const KIND_MULTI_INFO: u8 = 8;
+
+pub struct RecordRing<const N: usize> {
+    buf: UnsafeCell<[u8; N]>,
+    head: AtomicUsize,
+    tail: AtomicUsize,
+    overruns: AtomicU32,
+}
+
+// SAFETY: the writer only touches bytes outside `tail..head` and the reader only
+// touches bytes inside it; `head`/`tail` hand regions over with release/acquire.
+unsafe impl<const N: usize> Sync for RecordRing<N> {}
+
+impl<const N: usize> RecordRing<N> {
+    pub const fn new() -> Self {
+        Self {
+            buf: UnsafeCell::new([0; N]),
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+            overruns: AtomicU32::new(0),
+        }
+    }
+
+    pub fn split(&mut self) -> (RingWriter<'_, N>, RingReader<'_, N>) {
+        let ring = &*self;
+        (
+            RingWriter { ring },
+            RingReader {
+                ring,
+                frame_len: 0,
+                reported: 0,
+            },
+        )
+    }
+
+    // SAFETY: callers must own `start..start + len` on their side of the ring.
+    #[allow(clippy::mut_from_ref)]
+    unsafe fn bytes_mut(&self, start: usize, len: usize) -> &mut [u8] {
+        debug_assert!(start + len <= N);
+        unsafe { core::slice::from_raw_parts_mut(self.buf_ptr().add(start), len) }
+    }
+
+    fn buf_ptr(&self) -> *mut u8 {
+        self.buf.get().cast::<u8>()
+    }
+}
+
+impl<const N: usize> Default for RecordRing<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct RingWriter<'a, const N: usize> {
+    ring: &'a RecordRing<N>,
+}
+
+impl<const N: usize> RingWriter<'_, N> {
+    pub fn try_send<const RECORD_CAP: usize>(
+        &mut self,
+        record: &Record<RECORD_CAP>,
+    ) -> Result<(), TrySendError> {
+        self.try_send_parts(record.meta(), record.bytes())
+    }
+
+    pub fn try_send_parts(&mut self, meta: RecordMeta, bytes: &[u8]) -> Result<(), TrySendError> {
+        let mut grant = self.grant(meta, bytes.len())?;
+        grant.payload_mut().copy_from_slice(bytes);
+        grant.commit();
+        Ok(())
+    }
+
+    pub fn grant(
+        &mut self,
+        meta: RecordMeta,
+        payload_len: usize,
+    ) -> Result<RingGrant<'_, N>, TrySendError> {
+        let mut meta_buf = [0u8; META_MAX_LEN];
+        let meta_len = encode_meta(meta, &mut meta_buf);
+        let payload_start = FRAME_HEADER_LEN + meta_len;
+        let frame_len = payload_start + payload_len;
+
+        let header = u16::try_from(frame_len).ok();
+        let Some((header, start)) = header.zip(self.reserve(frame_len)) else {
+            self.count_overrun();
+            return Err(TrySendError::Full);
+        };
+        let frame = self.frame_mut(start, payload_start);
+        frame[..FRAME_HEADER_LEN].copy_from_slice(&header.to_le_bytes());
+        frame[FRAME_HEADER_LEN..].copy_from_slice(&meta_buf[..meta_len]);
+        Ok(RingGrant {
+            ring: self.ring,
+            start,
+            payload_start,
+            frame_len,
+        })
+    }
+
+    // Finds room for a contiguous frame, writing a wrap marker if it has to
+    // continue at the start of the buffer.
+    fn reserve(&mut self, frame_len: usize) -> Option<usize> {
+        if frame_len >= N {
+            return None;
+        }
+        let head = self.ring.head.load(Ordering::Relaxed);
+        let tail = self.ring.tail.load(Ordering::Acquire);
+
+        if head < tail {
+            return (head + frame_len < tail).then_some(head);
+        }
+
+        let end = head + frame_len;
+        if end < N || (end == N && tail != 0) {
+            return Some(head);
+        }
+        if frame_len >= tail {
+            return None;
+        }
+        if N - head >= FRAME_HEADER_LEN {
+            self.frame_mut(head, FRAME_HEADER_LEN)
+                .copy_from_slice(&WRAP_MARKER.to_le_bytes());
+        }
+        Some(0)
+    }
+
+    fn frame_mut(&mut self, start: usize, len: usize) -> &mut [u8] {
+        // SAFETY: `reserve` only hands out ranges the reader has released and
+        // the writer is unique, so no other reference aliases this range.
+        unsafe { self.ring.bytes_mut(start, len) }
+    }
+
+    fn count_overrun(&mut self) {
+        // Only the writer updates the counter, so a load/store pair is enough.
+        let overruns = self.ring.overruns.load(Ordering::Relaxed);
+        self.ring
+            .overruns
+            .store(overruns.wrapping_add(1), Ordering::Release);
+    }
+}
+
+pub struct RingGrant<'w, const N: usize> {
+    ring: &'w RecordRing<N>,
+    start: usize,
+    payload_start: usize,
+    frame_len: usize,
+}
+
+impl<const N: usize> RingGrant<'_, N> {
+    pub fn payload_mut(&mut self) -> &mut [u8] {
+        let len = self.frame_len - self.payload_start;
+        // SAFETY: the grant holds the writer borrow and the range was reserved for it.
+        unsafe { self.ring.bytes_mut(self.start + self.payload_start, len) }
+    }
+
+    pub fn commit(self) {
+        self.ring
+            .head
+            .store((self.start + self.frame_len) % N, Ordering::Release);
+    }
+}
+
+pub struct RingReader<'a, const N: usize> {
+    ring: &'a RecordRing<N>,
+    frame_len: usize,
+    reported: u32,
+}
+
+impl<const N: usize> RingReader<'_, N> {
+    pub fn peek(&mut self) -> Option<(RecordMeta, &[u8])> {
+        let head = self.ring.head.load(Ordering::Acquire);
+        let mut tail = self.ring.tail.load(Ordering::Relaxed);
+        loop {
+            if tail == head {
+                self.frame_len = 0;
+                return None;
+            }
+            if N - tail < FRAME_HEADER_LEN {
+                tail = 0;
+                continue;
+            }
+            let header = self.frame(tail, FRAME_HEADER_LEN);
+            let frame_len = usize::from(u16::from_le_bytes([header[0], header[1]]));
+            if frame_len == usize::from(WRAP_MARKER) {
+                tail = 0;
+                continue;
+            }
+            self.ring.tail.store(tail, Ordering::Release);
+
+            self.frame_len = frame_len;
+            let frame = self.frame(tail, frame_len);
+            let (meta, meta_len) = decode_meta(&frame[FRAME_HEADER_LEN..])?;
+            return Some((meta, &frame[FRAME_HEADER_LEN + meta_len..]));
+        }
+    }
+
+    pub fn consume(&mut self) {
+        if self.frame_len == 0 {
+            return;
+        }
+        let tail = self.ring.tail.load(Ordering::Relaxed);
+        self.ring
+            .tail
+            .store((tail + self.frame_len) % N, Ordering::Release);
+        self.frame_len = 0;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ring.head.load(Ordering::Acquire) == self.ring.tail.load(Ordering::Relaxed)
+    }
+
+    pub fn take_overruns(&mut self) -> u32 {
+        let overruns = self.ring.overruns.load(Ordering::Acquire);
+        let new = overruns.wrapping_sub(self.reported);
+        self.reported = overruns;
+        new
+    }
+
+    fn frame(&self, start: usize, len: usize) -> &[u8] {
+        debug_assert!(start + len <= N);
+        // SAFETY: bytes between `tail` and `head` were published by the writer
+        // and are not written again until the reader moves `tail` past them.
+        unsafe { core::slice::from_raw_parts(self.ring.buf_ptr().add(start), len) }
+    }
+}
+
+fn encode_meta(meta: RecordMeta, out: &mut [u8; META_MAX_LEN]) -> usize {
+    match meta {
+        RecordMeta::LoggedString {
+            level,
+            tag: None,
+            ts,
+        } => {
+            out[0] = KIND_LOG;
+            out[1] = level as u8;
+            out[2..10].copy_from_slice(&ts.to_le_bytes());
+            10
+        }
+        RecordMeta::LoggedString {
+            level,
+            tag: Some(tag),
+            ts,
+        } => {
+            out[0] = KIND_TAGGED_LOG;
+            out[1] = level as u8;
+            out[2..4].copy_from_slice(&tag.to_le_bytes());
+            out[4..12].copy_from_slice(&ts.to_le_bytes());
+            12
+        }
+        RecordMeta::Data {
+            topic_index,
+            instance,
+            ts,
+        } => {
+            out[0] = KIND_DATA;
+            out[1..3].copy_from_slice(&topic_index.to_le_bytes());
+            out[3] = instance;
+            out[4..12].copy_from_slice(&ts.to_le_bytes());
+            12
+        }
+        RecordMeta::Parameter { value } => {
+            out[0] = KIND_PARAMETER;
+            encode_parameter(value, &mut out[1..6]);
+            6
+        }
+        RecordMeta::ParameterDefault {
+            value,
+            default_types,
+        } => {
+            out[0] = KIND_PARAMETER_DEFAULT;
+            encode_parameter(value, &mut out[1..6]);
+            out[6] = default_types.bits();
+            7
+        }
+        RecordMeta::Dropout { duration_ms } => {
+            out[0] = KIND_DROPOUT;
+            out[1..3].copy_from_slice(&duration_ms.to_le_bytes());
+            3
+        }
+        RecordMeta::RemoveSubscription {
+            topic_index,
+            instance,
+        } => {
+            out[0] = KIND_REMOVE_SUBSCRIPTION;
+            out[1..3].copy_from_slice(&topic_index.to_le_bytes());
+            out[3] = instance;
+            4
+        }
+        RecordMeta::Info { key_len } => {
+            out[0] = KIND_INFO;
+            out[1] = key_len;
+            2
+        }
+        RecordMeta::MultiInfo {
+            key_len,
+            is_continued,
+        } => {
+            out[0] = KIND_MULTI_INFO;
+            out[1] = key_len;
+            out[2] = u8::from(is_continued);
+            3
+        }
+    }
+}
+
+fn decode_meta(bytes: &[u8]) -> Option<(RecordMeta, usize)> {
+    let u16_at = |at: usize| Some(u16::from_le_bytes(bytes.get(at..at + 2)?.try_into().ok()?));
+    let u64_at = |at: usize| Some(u64::from_le_bytes(bytes.get(at..at + 8)?.try_into().ok()?));
+    let byte_at = |at: usize| bytes.get(at).copied();
+
+    let meta = match byte_at(0)? {
+        KIND_LOG => (
+            RecordMeta::LoggedString {
+                level: LogLevel::try_from(byte_at(1)?).ok()?,
+                tag: None,
+                ts: u64_at(2)?,
+            },
+            10,
+        ),
+        KIND_TAGGED_LOG => (
+            RecordMeta::LoggedString {
+                level: LogLevel::try_from(byte_at(1)?).ok()?,
+                tag: Some(u16_at(2)?),
+                ts: u64_at(4)?,
+            },
+            12,
+        ),
+        KIND_DATA => (
+            RecordMeta::Data {
+                topic_index: u16_at(1)?,
+                instance: byte_at(3)?,
+                ts: u64_at(4)?,
+            },
+            12,
+        ),
+        KIND_PARAMETER => (
+            RecordMeta::Parameter {
+                value: decode_parameter(bytes.get(1..6)?)?,
+            },
+            6,
+        ),
+        KIND_PARAMETER_DEFAULT => (
+            RecordMeta::ParameterDefault {
+                value: decode_parameter(bytes.get(1..6)?)?,
+                default_types: DefaultTypes::from_bits(byte_at(6)?),
+            },
+            7,
+        ),
+        KIND_DROPOUT => (
+            RecordMeta::Dropout {
+                duration_ms: u16_at(1)?,
+            },
+            3,
+        ),
+        KIND_REMOVE_SUBSCRIPTION => (
+            RecordMeta::RemoveSubscription {
+                topic_index: u16_at(1)?,
+                instance: byte_at(3)?,
+            },
+            4,
+        ),
+        KIND_INFO => (
+            RecordMeta::Info {
+                key_len: byte_at(1)?,
+            },
+            2,
+        ),
+        KIND_MULTI_INFO => (
+            RecordMeta::MultiInfo {
+                key_len: byte_at(1)?,
+                is_continued: byte_at(2)? != 0,
+            },
+            3,
+        ),
+        _ => return None,
+    };
+    Some(meta)
+}
+
+fn encode_parameter(value: ParameterValue, out: &mut [u8]) {
+    let (kind, raw) = match value {
+        ParameterValue::I32(v) => (0, v.to_le_bytes()),
+        ParameterValue::F32(v) => (1, v.to_le_bytes()),
+    };
+    out[0] = kind;
+    out[1..5].copy_from_slice(&raw);
+}
+
+fn decode_parameter(bytes: &[u8]) -> Option<ParameterValue> {
+    let raw: [u8; 4] = bytes.get(1..5)?.try_into().ok()?;
+    match bytes.first()? {
+        0 => Some(ParameterValue::I32(i32::from_le_bytes(raw))),
+        1 => Some(ParameterValue::F32(f32::from_le_bytes(raw))),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recv<const N: usize>(
+        reader: &mut RingReader<'_, N>,
+    ) -> Option<(RecordMeta, std::vec::Vec<u8>)> {
+        let (meta, bytes) = reader.peek()?;
+        let frame = (meta, bytes.to_vec());
+        reader.consume();
+        Some(frame)
+    }
+
+    #[test]
+    fn frames_round_trip_with_compact_size() {
+        let mut ring = RecordRing::<64>::new();
+        let (mut tx, mut rx) = ring.split();
+        let data = Record::<128>::new_data(1, 2, 3, &[9; 20]).unwrap();
+        let param = Record::<128>::new_parameter(b"float P", ParameterValue::F32(0.5)).unwrap();
+
+        tx.try_send(&data).unwrap();
+        tx.try_send(&param).unwrap();
+        assert_eq!(
+            ring_used(&rx),
+            (FRAME_HEADER_LEN + 12 + 20) + (FRAME_HEADER_LEN + 6 + 7)
+        );
+
+        assert_eq!(recv(&mut rx), Some((data.meta(), data.bytes().to_vec())));
+        assert_eq!(recv(&mut rx), Some((param.meta(), param.bytes().to_vec())));
+        assert_eq!(recv(&mut rx), None);
+        assert!(rx.is_empty());
+    }
+
+    #[test]
+    fn frames_wrap_without_splitting() {
+        let mut ring = RecordRing::<50>::new();
+        let (mut tx, mut rx) = ring.split();
+        let log = |ts| Record::<128>::new_log(LogLevel::Info, None, ts, b"0123456789");
+
+        for round in 0..10 {
+            tx.try_send(&log(round)).unwrap();
+            let (meta, bytes) = recv(&mut rx).unwrap();
+            assert_eq!(meta, log(round).meta());
+            assert_eq!(bytes, b"0123456789");
+        }
+    }
+
+    #[test]
+    fn full_ring_counts_overruns() {
+        let mut ring = RecordRing::<32>::new();
+        let (mut tx, mut rx) = ring.split();
+        let dropout = Record::<128>::new_dropout(7);
+
+        let mut sent = 0;
+        while tx.try_send(&dropout).is_ok() {
+            sent += 1;
+        }
+        assert_eq!(sent, 6);
+        assert_eq!(
+            tx.try_send(&Record::<128>::new_log(LogLevel::Info, None, 0, &[0; 64])),
+            Err(TrySendError::Full)
+        );
+        assert_eq!(rx.take_overruns(), 2);
+        assert_eq!(rx.take_overruns(), 0);
+
+        recv(&mut rx).unwrap();
+        recv(&mut rx).unwrap();
+        assert!(tx.try_send(&dropout).is_ok());
+    }
+
+    fn ring_used<const N: usize>(reader: &RingReader<'_, N>) -> usize {
+        let head = reader.ring.head.load(Ordering::Relaxed);
+        let tail = reader.ring.tail.load(Ordering::Relaxed);
+        (head + N - tail) % N
+    }
+}
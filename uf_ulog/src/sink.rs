@@ -0,0 +1,36 @@
+use std::sync::mpsc::SyncSender;
+use std::sync::Mutex;
+
+use crate::exporter::StreamingReady;
+use crate::{Record, RecordSender, TrySendError, ULogCoreExporter, ULogRegistry};
+
+pub trait RecordSink<const RECORD_CAP: usize> {
+    fn try_send(&self, record: Record<RECORD_CAP>) -> Result<(), TrySendError>;
+}
+
+impl<const RECORD_CAP: usize> RecordSink<RECORD_CAP> for Mutex<RecordSender<'_, RECORD_CAP>> {
+    fn try_send(&self, record: Record<RECORD_CAP>) -> Result<(), TrySendError> {
+        // A poisoned lock only means another logging call panicked mid-send.
+        let mut sender = self.lock().unwrap_or_else(|err| err.into_inner());
+        sender.try_send(record)
+    }
+}
+
+impl<const RECORD_CAP: usize> RecordSink<RECORD_CAP> for SyncSender<Record<RECORD_CAP>> {
+    fn try_send(&self, record: Record<RECORD_CAP>) -> Result<(), TrySendError> {
+        SyncSender::try_send(self, record).map_err(|_| TrySendError::Full)
+    }
+}
+
+impl<W, R, const RECORD_CAP: usize, const MAX_MULTI_IDS: usize, const MAX_STREAMS: usize>
+    RecordSink<RECORD_CAP>
+    for Mutex<ULogCoreExporter<W, R, StreamingReady, RECORD_CAP, MAX_MULTI_IDS, MAX_STREAMS>>
+where
+    W: embedded_io::Write,
+    R: ULogRegistry,
+{
+    fn try_send(&self, record: Record<RECORD_CAP>) -> Result<(), TrySendError> {
+        let mut exporter = self.lock().unwrap_or_else(|err| err.into_inner());
+        exporter.accept(record).map_err(|_| TrySendError::Closed)
+    }
+}
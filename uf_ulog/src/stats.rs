@@ -0,0 +1,123 @@
+use crate::{EncodeError, LogLevel, ULogData};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StreamStats {
+    pub messages: u32,
+    pub bytes: u64,
+}
+
+// Per-slot counters are indexed like `wire::stream_slot`:
+// `topic_index * MAX_MULTI_IDS + instance`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportStats<const MAX_STREAMS: usize> {
+    pub streams: [StreamStats; MAX_STREAMS],
+    pub logs_by_level: [u32; 8],
+    pub total_bytes: u64,
+}
+
+impl<const MAX_STREAMS: usize> ExportStats<MAX_STREAMS> {
+    pub const fn new() -> Self {
+        Self {
+            streams: [StreamStats {
+                messages: 0,
+                bytes: 0,
+            }; MAX_STREAMS],
+            logs_by_level: [0; 8],
+            total_bytes: 0,
+        }
+    }
+
+    pub fn logs(&self, level: LogLevel) -> u32 {
+        self.logs_by_level[level_index(level)]
+    }
+
+    pub(crate) fn record_stream(&mut self, slot: usize, bytes: usize) {
+        if let Some(stream) = self.streams.get_mut(slot) {
+            stream.messages = stream.messages.saturating_add(1);
+            stream.bytes = stream.bytes.saturating_add(bytes as u64);
+        }
+    }
+
+    pub(crate) fn record_log(&mut self, level: LogLevel) {
+        let count = &mut self.logs_by_level[level_index(level)];
+        *count = count.saturating_add(1);
+    }
+
+    pub(crate) fn record_bytes(&mut self, bytes: usize) {
+        self.total_bytes = self.total_bytes.saturating_add(bytes as u64);
+    }
+}
+
+impl<const MAX_STREAMS: usize> Default for ExportStats<MAX_STREAMS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn level_index(level: LogLevel) -> usize {
+    usize::from(level as u8 - LogLevel::Emerg as u8)
+}
+
+// Topics written by `emit_stats`; add both to the registry to use them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportStatsData {
+    pub timestamp: u64,
+    pub total_bytes: u64,
+    pub dropped_streams: u32,
+    pub queue_overruns: u32,
+    pub logs_by_level: [u32; 8],
+}
+
+impl ULogData for ExportStatsData {
+    const FORMAT: &'static str = "uint64_t timestamp;uint64_t total_bytes;uint32_t dropped_streams;uint32_t queue_overruns;uint32_t[8] logs_by_level;";
+    const NAME: &'static str = "export_stats";
+    const WIRE_SIZE: usize = 56;
+
+    fn encode(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        let out = buf
+            .get_mut(..Self::WIRE_SIZE)
+            .ok_or(EncodeError::BufferOverflow)?;
+        out[0..8].copy_from_slice(&self.timestamp.to_le_bytes());
+        out[8..16].copy_from_slice(&self.total_bytes.to_le_bytes());
+        out[16..20].copy_from_slice(&self.dropped_streams.to_le_bytes());
+        out[20..24].copy_from_slice(&self.queue_overruns.to_le_bytes());
+        for (chunk, count) in out[24..].chunks_exact_mut(4).zip(self.logs_by_level) {
+            chunk.copy_from_slice(&count.to_le_bytes());
+        }
+        Ok(Self::WIRE_SIZE)
+    }
+
+    fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamStatsData {
+    pub timestamp: u64,
+    pub bytes: u64,
+    pub messages: u32,
+    pub slot: u16,
+}
+
+impl ULogData for StreamStatsData {
+    const FORMAT: &'static str =
+        "uint64_t timestamp;uint64_t bytes;uint32_t messages;uint16_t slot;";
+    const NAME: &'static str = "export_stream_stats";
+    const WIRE_SIZE: usize = 22;
+
+    fn encode(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        let out = buf
+            .get_mut(..Self::WIRE_SIZE)
+            .ok_or(EncodeError::BufferOverflow)?;
+        out[0..8].copy_from_slice(&self.timestamp.to_le_bytes());
+        out[8..16].copy_from_slice(&self.bytes.to_le_bytes());
+        out[16..20].copy_from_slice(&self.messages.to_le_bytes());
+        out[20..22].copy_from_slice(&self.slot.to_le_bytes());
+        Ok(Self::WIRE_SIZE)
+    }
+
+    fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+}
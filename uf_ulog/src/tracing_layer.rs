@@ -0,0 +1,364 @@
+use core::fmt::Write as _;
+
+use tracing_core::field::{Field, Visit};
+use tracing_core::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::level::static_level_enabled;
+use crate::producer::TextWriter;
+use crate::{Clock, LevelFilter, LogLevel, Record, RecordSink, TopicOf, ULogData};
+
+struct FieldTopic {
+    topic_index: u16,
+    fields: &'static [&'static str],
+}
+
+pub struct ULogLayer<S, C, const RECORD_CAP: usize = 128> {
+    sink: S,
+    clock: C,
+    tags: &'static [(&'static str, u16)],
+    field_topic: Option<FieldTopic>,
+    level_filter: LevelFilter,
+}
+
+impl<S, C, const RECORD_CAP: usize> ULogLayer<S, C, RECORD_CAP>
+where
+    S: RecordSink<RECORD_CAP>,
+    C: Clock,
+{
+    pub const fn new(sink: S, clock: C) -> Self {
+        Self {
+            sink,
+            clock,
+            tags: &[],
+            field_topic: None,
+            level_filter: LevelFilter::new(LogLevel::Debug),
+        }
+    }
+
+    pub const fn with_span_tags(mut self, tags: &'static [(&'static str, u16)]) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    // Numeric event fields listed in `fields` are written to `T`, which must be
+    // laid out as `uint64_t timestamp;float[fields.len()] <name>;`; missing
+    // fields are logged as NaN. Panics (at compile time in const context) on
+    // a layout mismatch, more than 16 fields or a record over `RECORD_CAP`.
+    pub const fn with_field_topic<R, T>(mut self, fields: &'static [&'static str]) -> Self
+    where
+        T: TopicOf<R> + ULogData,
+    {
+        assert!(fields.len() <= MAX_FIELDS, "at most 16 field topic values");
+        assert!(
+            T::WIRE_SIZE == 8 + 4 * fields.len() && is_field_format(T::FORMAT, fields.len()),
+            "field topic format must be `uint64_t timestamp;float[N] <name>;`"
+        );
+        assert!(T::WIRE_SIZE <= RECORD_CAP, "field topic exceeds RECORD_CAP");
+        self.field_topic = Some(FieldTopic {
+            topic_index: T::TOPIC.id(),
+            fields,
+        });
+        self
+    }
+
+    // Filters the logged strings only; field data is written at any level.
+    pub const fn with_level_filter(mut self, level_filter: LevelFilter) -> Self {
+        self.level_filter = level_filter;
+        self
+    }
+
+    pub fn sink(&self) -> &S {
+        &self.sink
+    }
+
+    fn span_tag<Sub>(&self, event: &Event<'_>, ctx: &Context<'_, Sub>) -> Option<u16>
+    where
+        Sub: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        // The innermost span with a configured tag wins.
+        ctx.event_scope(event)?.find_map(|span| {
+            self.tags
+                .iter()
+                .find(|(name, _)| *name == span.name())
+                .map(|&(_, tag)| tag)
+        })
+    }
+
+    fn send_fields(&self, ts: u64, values: &[f32]) {
+        let Some(topic) = &self.field_topic else {
+            return;
+        };
+        let mut payload = [0u8; RECORD_CAP];
+        let out = &mut payload[..8 + values.len() * 4];
+        out[..8].copy_from_slice(&ts.to_le_bytes());
+        for (chunk, value) in out[8..].chunks_exact_mut(4).zip(values) {
+            chunk.copy_from_slice(&value.to_le_bytes());
+        }
+        if let Some(record) = Record::new_data(topic.topic_index, 0, ts, out) {
+            let _ = self.sink.try_send(record);
+        }
+    }
+}
+
+impl<S, C, Sub, const RECORD_CAP: usize> Layer<Sub> for ULogLayer<S, C, RECORD_CAP>
+where
+    S: RecordSink<RECORD_CAP> + Send + Sync + 'static,
+    C: Clock + Send + Sync + 'static,
+    Sub: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, Sub>) {
+        let level = log_level(*event.metadata().level());
+        let tag = self.span_tag(event, &ctx);
+        let log_enabled = static_level_enabled(level) && self.level_filter.allows(level, tag);
+        if !log_enabled && self.field_topic.is_none() {
+            return;
+        }
+
+        let ts = self.clock.now_micros();
+        let fields = self.field_topic.as_ref().map_or(&[][..], |t| t.fields);
+        let mut visitor = EventVisitor::<RECORD_CAP>::new(fields);
+        event.record(&mut visitor);
+
+        if log_enabled {
+            let record = Record::new_log(level, tag, ts, &visitor.text.finish());
+            // Overruns are counted by the sink; tracing must never block or fail.
+            let _ = self.sink.try_send(record);
+        }
+
+        if visitor.matched {
+            self.send_fields(ts, &visitor.values[..visitor.fields.len()]);
+        }
+    }
+}
+
+const MAX_FIELDS: usize = 16;
+
+struct EventVisitor<const RECORD_CAP: usize> {
+    text: TextWriter<RECORD_CAP>,
+    fields: &'static [&'static str],
+    values: [f32; MAX_FIELDS],
+    matched: bool,
+}
+
+impl<const RECORD_CAP: usize> EventVisitor<RECORD_CAP> {
+    fn new(fields: &'static [&'static str]) -> Self {
+        Self {
+            text: TextWriter::new(),
+            fields,
+            values: [f32::NAN; MAX_FIELDS],
+            matched: false,
+        }
+    }
+
+    fn record_number(&mut self, field: &Field, value: f64) {
+        if let Some(i) = self.fields.iter().position(|name| *name == field.name()) {
+            self.values[i] = value as f32;
+            self.matched = true;
+        }
+    }
+}
+
+impl<const RECORD_CAP: usize> Visit for EventVisitor<RECORD_CAP> {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.record_number(field, value);
+        self.record_debug(field, &value);
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.record_number(field, value as f64);
+        self.record_debug(field, &value);
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.record_number(field, value as f64);
+        self.record_debug(field, &value);
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn core::fmt::Debug) {
+        let _ = if field.name() == "message" {
+            write!(self.text, "{value:?}")
+        } else {
+            write!(self.text, " {}={value:?}", field.name())
+        };
+    }
+}
+
+const fn is_field_format(format: &str, len: usize) -> bool {
+    const PREFIX: &[u8] = b"uint64_t timestamp;float[";
+    let bytes = format.as_bytes();
+    if bytes.len() < PREFIX.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < PREFIX.len() {
+        if bytes[i] != PREFIX[i] {
+            return false;
+        }
+        i += 1;
+    }
+    let mut count = 0;
+    let digits = i;
+    while i < bytes.len() && bytes[i].is_ascii_digit() {
+        count = count * 10 + (bytes[i] - b'0') as usize;
+        i += 1;
+    }
+    if i == digits || count != len || i + 2 >= bytes.len() || bytes[i] != b']' {
+        return false;
+    }
+    i += 1;
+    if bytes[i] != b' ' {
+        return false;
+    }
+    i += 1;
+    // One field name, terminated by the final `;`.
+    let name = i;
+    while i < bytes.len() && bytes[i] != b';' {
+        i += 1;
+    }
+    i > name && i + 1 == bytes.len()
+}
+
+fn log_level(level: Level) -> LogLevel {
+    match level {
+        Level::ERROR => LogLevel::Err,
+        Level::WARN => LogLevel::Warning,
+        Level::INFO => LogLevel::Info,
+        Level::DEBUG | Level::TRACE => LogLevel::Debug,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EncodeError, RecordMeta, Topic};
+    use std::sync::mpsc::sync_channel;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    const CAP: usize = 64;
+
+    fn clock() -> u64 {
+        7
+    }
+
+    enum LayerMessages {}
+
+    struct FieldData<const N: usize>;
+
+    impl<const N: usize> ULogData for FieldData<N> {
+        const FORMAT: &'static str = match N {
+            1 => "uint64_t timestamp;float[1] values;",
+            2 => "uint64_t timestamp;float[2] values;",
+            _ => "",
+        };
+        const NAME: &'static str = "fields";
+        const WIRE_SIZE: usize = 8 + 4 * N;
+
+        fn encode(&self, _buf: &mut [u8]) -> Result<usize, EncodeError> {
+            Err(EncodeError::BufferOverflow)
+        }
+
+        fn timestamp(&self) -> u64 {
+            0
+        }
+    }
+
+    impl<const N: usize> TopicOf<LayerMessages> for FieldData<N> {
+        const TOPIC: Topic<Self> = Topic::new(2);
+    }
+
+    type Nav = FieldData<2>;
+    type Alt = FieldData<1>;
+
+    #[test]
+    fn events_become_tagged_strings_and_field_data() {
+        let (tx, rx) = sync_channel::<Record<CAP>>(8);
+        let layer = ULogLayer::<_, _, CAP>::new(tx, clock)
+            .with_span_tags(&[("nav", 3)])
+            .with_field_topic::<LayerMessages, Nav>(&["alt", "speed"]);
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("nav");
+            let _guard = span.enter();
+            tracing::warn!(alt = 12.5, mode = "hold", "climb");
+        });
+
+        if static_level_enabled(LogLevel::Warning) {
+            let log = rx.try_recv().unwrap();
+            assert_eq!(
+                log.meta(),
+                RecordMeta::LoggedString {
+                    level: LogLevel::Warning,
+                    tag: Some(3),
+                    ts: 7
+                }
+            );
+            assert_eq!(log.bytes(), b"climb alt=12.5 mode=\"hold\"");
+        }
+
+        let data = rx.try_recv().unwrap();
+        assert_eq!(
+            data.meta(),
+            RecordMeta::Data {
+                topic_index: 2,
+                instance: 0,
+                ts: 7
+            }
+        );
+        assert_eq!(&data.bytes()[8..12], &12.5f32.to_le_bytes());
+        assert!(f32::from_le_bytes(data.bytes()[12..16].try_into().unwrap()).is_nan());
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn level_filter_drops_strings_but_keeps_field_data() {
+        let (tx, rx) = sync_channel::<Record<CAP>>(8);
+        let layer = ULogLayer::<_, _, CAP>::new(tx, clock)
+            .with_level_filter(LevelFilter::new(LogLevel::Warning))
+            .with_field_topic::<LayerMessages, Alt>(&["alt"]);
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(alt = 3.0, "level");
+            tracing::error!("fault");
+        });
+
+        assert_eq!(rx.try_recv().unwrap().kind(), crate::RecordKind::Data);
+        if static_level_enabled(LogLevel::Err) {
+            assert_eq!(rx.try_recv().unwrap().bytes(), b"fault");
+        }
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn field_topic_formats_are_checked() {
+        assert!(is_field_format("uint64_t timestamp;float[2] values;", 2));
+        assert!(is_field_format("uint64_t timestamp;float[12] v;", 12));
+        assert!(!is_field_format("uint64_t timestamp;float[2] values;", 1));
+        assert!(!is_field_format(
+            "uint64_t timestamp;float[2] values;float x;",
+            2
+        ));
+        assert!(!is_field_format("uint64_t timestamp;float[] values;", 0));
+        assert!(!is_field_format("uint64_t timestamp;float[2];", 2));
+        assert!(!is_field_format("uint64_t timestamp;double[2] values;", 2));
+    }
+
+    #[test]
+    #[should_panic(expected = "field topic format")]
+    fn short_field_list_is_rejected() {
+        let (tx, _rx) = sync_channel::<Record<CAP>>(1);
+        let _ =
+            ULogLayer::<_, _, CAP>::new(tx, clock).with_field_topic::<LayerMessages, Nav>(&["alt"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "RECORD_CAP")]
+    fn field_topic_over_record_cap_is_rejected() {
+        let (tx, _rx) = sync_channel::<Record<12>>(1);
+        let _ = ULogLayer::<_, _, 12>::new(tx, clock)
+            .with_field_topic::<LayerMessages, Nav>(&["alt", "speed"]);
+    }
+}
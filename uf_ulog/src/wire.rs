@@ -1,11 +1,13 @@
-use crate::{MessageMeta, ULogRegistry};
+use crate::{DefaultTypes, MessageMeta, ParameterValue, RecordMeta, ULogRegistry};
 
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ExportStep {
     Progressed,
     Idle,
 }
 
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ExportError<WriteError> {
     Write(WriteError),
@@ -14,11 +16,85 @@ pub enum ExportError<WriteError> {
     InvalidMultiId,
     TooManyStreams,
     MessageTooLarge,
+    AppendedOffsetsFull,
+    Busy,
+}
+
+// Controls when the exporter inserts 'S' sync messages on its own; sync
+// messages let readers recover after corrupted bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SyncPolicy {
+    #[default]
+    Manual,
+    EveryBytes(u32),
+    EveryMicros(u64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FlagBits {
+    pub compat_flags: [u8; 8],
+    pub incompat_flags: [u8; 8],
+    pub appended_offsets: [u64; 3],
+}
+
+impl FlagBits {
+    pub const COMPAT_DEFAULT_PARAMETERS: u8 = 1 << 0;
+    pub const INCOMPAT_DATA_APPENDED: u8 = 1 << 0;
+
+    pub const fn new() -> Self {
+        Self {
+            compat_flags: [0; 8],
+            incompat_flags: [0; 8],
+            appended_offsets: [0; 3],
+        }
+    }
+
+    pub const fn has_appended_data(&self) -> bool {
+        self.incompat_flags[0] & Self::INCOMPAT_DATA_APPENDED != 0
+    }
+
+    pub fn push_appended_offset(&mut self, offset: u64) -> bool {
+        let Some(slot) = self.appended_offsets.iter_mut().find(|slot| **slot == 0) else {
+            return false;
+        };
+        *slot = offset;
+        self.incompat_flags[0] |= Self::INCOMPAT_DATA_APPENDED;
+        true
+    }
+
+    pub fn decode(payload: &[u8]) -> Option<Self> {
+        let payload: &[u8; FLAG_BITS_PAYLOAD_LEN] =
+            payload.get(..FLAG_BITS_PAYLOAD_LEN)?.try_into().ok()?;
+        let mut flags = Self::new();
+        flags.compat_flags.copy_from_slice(&payload[0..8]);
+        flags.incompat_flags.copy_from_slice(&payload[8..16]);
+        for (offset, chunk) in flags
+            .appended_offsets
+            .iter_mut()
+            .zip(payload[16..].chunks_exact(8))
+        {
+            let mut raw = [0u8; 8];
+            raw.copy_from_slice(chunk);
+            *offset = u64::from_le_bytes(raw);
+        }
+        Some(flags)
+    }
+
+    pub fn encode(&self) -> [u8; FLAG_BITS_PAYLOAD_LEN] {
+        let mut payload = [0u8; FLAG_BITS_PAYLOAD_LEN];
+        payload[0..8].copy_from_slice(&self.compat_flags);
+        payload[8..16].copy_from_slice(&self.incompat_flags);
+        for (chunk, offset) in payload[16..].chunks_exact_mut(8).zip(self.appended_offsets) {
+            chunk.copy_from_slice(&offset.to_le_bytes());
+        }
+        payload
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum MessageType {
     AddSubscription,
+    RemoveSubscription,
     TaggedLoggedString,
     Data,
     Format,
@@ -26,12 +102,36 @@ pub(crate) enum MessageType {
     Parameter,
     FlagBits,
     Sync,
+    Info,
+    MultiInfo,
+    ParameterDefault,
+    Dropout,
 }
 
 impl MessageType {
+    pub(crate) const fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            b'A' => Some(Self::AddSubscription),
+            b'R' => Some(Self::RemoveSubscription),
+            b'C' => Some(Self::TaggedLoggedString),
+            b'D' => Some(Self::Data),
+            b'F' => Some(Self::Format),
+            b'L' => Some(Self::LoggedString),
+            b'P' => Some(Self::Parameter),
+            b'B' => Some(Self::FlagBits),
+            b'S' => Some(Self::Sync),
+            b'I' => Some(Self::Info),
+            b'M' => Some(Self::MultiInfo),
+            b'Q' => Some(Self::ParameterDefault),
+            b'O' => Some(Self::Dropout),
+            _ => None,
+        }
+    }
+
     pub(crate) const fn as_u8(self) -> u8 {
         match self {
             Self::AddSubscription => b'A',
+            Self::RemoveSubscription => b'R',
             Self::TaggedLoggedString => b'C',
             Self::Data => b'D',
             Self::Format => b'F',
@@ -39,11 +139,20 @@ impl MessageType {
             Self::Parameter => b'P',
             Self::FlagBits => b'B',
             Self::Sync => b'S',
+            Self::Info => b'I',
+            Self::MultiInfo => b'M',
+            Self::ParameterDefault => b'Q',
+            Self::Dropout => b'O',
         }
     }
 }
 
+pub(crate) const ULOG_HEADER_LEN: usize = 16;
+pub(crate) const MESSAGE_HEADER_LEN: usize = 3;
 pub(crate) const ULOG_HEADER_MAGIC: [u8; 8] = [0x55, 0x4c, 0x6f, 0x67, 0x01, 0x12, 0x35, 0x01];
+pub(crate) const FLAG_BITS_PAYLOAD_LEN: usize = 40;
+// File header (16 bytes) followed by the FlagBits message header (3 bytes).
+pub(crate) const FLAG_BITS_PAYLOAD_OFFSET: u64 = 19;
 pub(crate) const ULOG_SYNC_MAGIC: [u8; 8] = [0x2F, 0x73, 0x13, 0x20, 0x25, 0x0C, 0xBB, 0x12];
 
 pub fn registry_entry<R: ULogRegistry, E>(
@@ -98,35 +207,10 @@ pub fn message_header<E>(
     Ok(header)
 }
 
-pub fn format_payload_len<E>(name: &str, format: &str) -> Result<usize, ExportError<E>> {
-    let name_bytes = name.as_bytes();
-    let format_bytes = format.as_bytes();
-    let format_offset = checked_total_len(name_bytes.len(), 1, usize::MAX)?;
-    checked_total_len(format_offset, format_bytes.len(), usize::MAX)
-}
-
-pub fn add_subscription_payload_len<E>(name: &str) -> Result<usize, ExportError<E>> {
-    checked_total_len(3, name.len(), usize::MAX)
-}
-
 pub fn data_payload_len<E>(data_len: usize) -> Result<usize, ExportError<E>> {
     checked_total_len(2, data_len, usize::MAX)
 }
 
-pub fn log_payload_len<E>(text_len: usize) -> Result<usize, ExportError<E>> {
-    checked_total_len(9, text_len, usize::MAX)
-}
-
-pub fn tagged_log_payload_len<E>(text_len: usize) -> Result<usize, ExportError<E>> {
-    checked_total_len(11, text_len, usize::MAX)
-}
-
-pub fn parameter_payload_len<E>(key: &[u8], value: &[u8]) -> Result<usize, ExportError<E>> {
-    let _ = u8::try_from(key.len()).map_err(|_e| ExportError::MessageTooLarge)?;
-    let value_offset = checked_total_len(1, key.len(), usize::MAX)?;
-    checked_total_len(value_offset, value.len(), usize::MAX)
-}
-
 pub fn add_subscription_prefix(multi_id: u8, msg_id: u16) -> [u8; 3] {
     let mut prefix = [0u8; 3];
     prefix[0] = multi_id;
@@ -134,6 +218,10 @@ pub fn add_subscription_prefix(multi_id: u8, msg_id: u16) -> [u8; 3] {
     prefix
 }
 
+pub fn remove_subscription_payload(msg_id: u16) -> [u8; 2] {
+    msg_id.to_le_bytes()
+}
+
 pub fn data_prefix(msg_id: u16) -> [u8; 2] {
     msg_id.to_le_bytes()
 }
@@ -153,18 +241,259 @@ pub fn tagged_log_prefix(level: u8, tag: u16, timestamp: u64) -> [u8; 11] {
     prefix
 }
 
+pub fn dropout_payload(duration_ms: u16) -> [u8; 2] {
+    duration_ms.to_le_bytes()
+}
+
+pub fn dropout_duration_ms(from_micros: u64, to_micros: u64) -> u16 {
+    let millis = to_micros.saturating_sub(from_micros) / 1000;
+    u16::try_from(millis).unwrap_or(u16::MAX)
+}
+
 pub fn parameter_prefix<E>(key: &[u8]) -> Result<[u8; 1], ExportError<E>> {
     let key_len = u8::try_from(key.len()).map_err(|_e| ExportError::MessageTooLarge)?;
     Ok([key_len])
 }
 
+pub fn parameter_default_prefix<E>(
+    default_types: u8,
+    key: &[u8],
+) -> Result<[u8; 2], ExportError<E>> {
+    let key_len = u8::try_from(key.len()).map_err(|_e| ExportError::MessageTooLarge)?;
+    Ok([default_types, key_len])
+}
+
+pub fn info_prefix<E>(key: &[u8]) -> Result<[u8; 1], ExportError<E>> {
+    let key_len = u8::try_from(key.len()).map_err(|_e| ExportError::MessageTooLarge)?;
+    Ok([key_len])
+}
+
+pub fn multi_info_prefix<E>(is_continued: bool, key: &[u8]) -> Result<[u8; 2], ExportError<E>> {
+    let key_len = u8::try_from(key.len()).map_err(|_e| ExportError::MessageTooLarge)?;
+    Ok([u8::from(is_continued), key_len])
+}
+
+// One encoded message as header plus fixed prefix, borrowed body parts and an
+// owned tail. Every writer goes through these constructors, so the exporters
+// and the slice encoder cannot drift apart on the wire layout.
+pub(crate) struct Message<'a> {
+    head: [u8; MESSAGE_HEADER_LEN + 11],
+    head_len: usize,
+    body: [&'a [u8]; 3],
+    tail: [u8; 4],
+    tail_len: usize,
+}
+
+impl<'a> Message<'a> {
+    fn new<E>(
+        msg_type: MessageType,
+        prefix: &[u8],
+        body: [&'a [u8]; 3],
+        tail: &[u8],
+    ) -> Result<Self, ExportError<E>> {
+        let mut payload_len = checked_total_len(prefix.len(), tail.len(), usize::MAX)?;
+        for part in body {
+            payload_len = checked_total_len(payload_len, part.len(), usize::MAX)?;
+        }
+        let header = message_header(payload_len, msg_type)?;
+        let head_len = MESSAGE_HEADER_LEN + prefix.len();
+        let mut head = [0u8; MESSAGE_HEADER_LEN + 11];
+        head[..MESSAGE_HEADER_LEN].copy_from_slice(&header);
+        head[MESSAGE_HEADER_LEN..head_len].copy_from_slice(prefix);
+        let mut raw_tail = [0u8; 4];
+        raw_tail[..tail.len()].copy_from_slice(tail);
+        Ok(Self {
+            head,
+            head_len,
+            body,
+            tail: raw_tail,
+            tail_len: tail.len(),
+        })
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.parts().iter().map(|part| part.len()).sum()
+    }
+
+    pub(crate) fn parts(&self) -> [&[u8]; 5] {
+        [
+            &self.head[..self.head_len],
+            self.body[0],
+            self.body[1],
+            self.body[2],
+            &self.tail[..self.tail_len],
+        ]
+    }
+
+    pub(crate) fn flag_bits<E>(
+        payload: &'a [u8; FLAG_BITS_PAYLOAD_LEN],
+    ) -> Result<Self, ExportError<E>> {
+        Self::new(MessageType::FlagBits, &[], [payload, &[], &[]], &[])
+    }
+
+    pub(crate) fn sync<E>() -> Result<Self, ExportError<E>> {
+        Self::new(MessageType::Sync, &[], [&ULOG_SYNC_MAGIC, &[], &[]], &[])
+    }
+
+    pub(crate) fn format<E>(name: &'a str, format: &'a str) -> Result<Self, ExportError<E>> {
+        Self::new(
+            MessageType::Format,
+            &[],
+            [name.as_bytes(), b":", format.as_bytes()],
+            &[],
+        )
+    }
+
+    pub(crate) fn add_subscription<E>(
+        multi_id: u8,
+        msg_id: u16,
+        name: &'a str,
+    ) -> Result<Self, ExportError<E>> {
+        let prefix = add_subscription_prefix(multi_id, msg_id);
+        Self::new(
+            MessageType::AddSubscription,
+            &prefix,
+            [name.as_bytes(), &[], &[]],
+            &[],
+        )
+    }
+
+    pub(crate) fn remove_subscription<E>(msg_id: u16) -> Result<Self, ExportError<E>> {
+        let payload = remove_subscription_payload(msg_id);
+        Self::new(
+            MessageType::RemoveSubscription,
+            &payload,
+            [&[], &[], &[]],
+            &[],
+        )
+    }
+
+    pub(crate) fn data<E>(msg_id: u16, data: &'a [u8]) -> Result<Self, ExportError<E>> {
+        Self::new(
+            MessageType::Data,
+            &data_prefix(msg_id),
+            [data, &[], &[]],
+            &[],
+        )
+    }
+
+    pub(crate) fn dropout<E>(duration_ms: u16) -> Result<Self, ExportError<E>> {
+        let payload = dropout_payload(duration_ms);
+        Self::new(MessageType::Dropout, &payload, [&[], &[], &[]], &[])
+    }
+
+    pub(crate) fn log<E>(
+        level: u8,
+        tag: Option<u16>,
+        timestamp: u64,
+        text: &'a [u8],
+    ) -> Result<Self, ExportError<E>> {
+        match tag {
+            Some(tag) => Self::new(
+                MessageType::TaggedLoggedString,
+                &tagged_log_prefix(level, tag, timestamp),
+                [text, &[], &[]],
+                &[],
+            ),
+            None => Self::new(
+                MessageType::LoggedString,
+                &log_prefix(level, timestamp),
+                [text, &[], &[]],
+                &[],
+            ),
+        }
+    }
+
+    pub(crate) fn parameter<E>(
+        key: &'a [u8],
+        value: ParameterValue,
+    ) -> Result<Self, ExportError<E>> {
+        let prefix = parameter_prefix(key)?;
+        let raw = parameter_value_bytes(value);
+        Self::new(MessageType::Parameter, &prefix, [key, &[], &[]], &raw)
+    }
+
+    pub(crate) fn parameter_default<E>(
+        key: &'a [u8],
+        value: ParameterValue,
+        default_types: DefaultTypes,
+    ) -> Result<Self, ExportError<E>> {
+        let prefix = parameter_default_prefix(default_types.bits(), key)?;
+        let raw = parameter_value_bytes(value);
+        Self::new(
+            MessageType::ParameterDefault,
+            &prefix,
+            [key, &[], &[]],
+            &raw,
+        )
+    }
+
+    pub(crate) fn info<E>(key: &'a [u8], value: &'a [u8]) -> Result<Self, ExportError<E>> {
+        let prefix = info_prefix(key)?;
+        Self::new(MessageType::Info, &prefix, [key, value, &[]], &[])
+    }
+
+    pub(crate) fn multi_info<E>(
+        is_continued: bool,
+        key: &'a [u8],
+        value: &'a [u8],
+    ) -> Result<Self, ExportError<E>> {
+        let prefix = multi_info_prefix(is_continued, key)?;
+        Self::new(MessageType::MultiInfo, &prefix, [key, value, &[]], &[])
+    }
+
+    // Encodes a record whose stream, if any, resolved to `msg_id`.
+    pub(crate) fn record<E>(
+        meta: RecordMeta,
+        bytes: &'a [u8],
+        msg_id: u16,
+    ) -> Result<Self, ExportError<E>> {
+        match meta {
+            RecordMeta::LoggedString { level, tag, ts } => Self::log(level as u8, tag, ts, bytes),
+            RecordMeta::Data { .. } => Self::data(msg_id, bytes),
+            RecordMeta::Dropout { duration_ms } => Self::dropout(duration_ms),
+            RecordMeta::RemoveSubscription { .. } => Self::remove_subscription(msg_id),
+            RecordMeta::Parameter { value } => Self::parameter(bytes, value),
+            RecordMeta::ParameterDefault {
+                value,
+                default_types,
+            } => Self::parameter_default(bytes, value, default_types),
+            RecordMeta::Info { key_len } => {
+                let (key, value) = split_key(bytes, key_len)?;
+                Self::info(key, value)
+            }
+            RecordMeta::MultiInfo {
+                key_len,
+                is_continued,
+            } => {
+                let (key, value) = split_key(bytes, key_len)?;
+                Self::multi_info(is_continued, key, value)
+            }
+        }
+    }
+}
+
+fn split_key<E>(bytes: &[u8], key_len: u8) -> Result<(&[u8], &[u8]), ExportError<E>> {
+    bytes
+        .split_at_checked(usize::from(key_len))
+        .ok_or(ExportError::MessageTooLarge)
+}
+
+fn parameter_value_bytes(value: ParameterValue) -> [u8; 4] {
+    match value {
+        ParameterValue::I32(v) => v.to_le_bytes(),
+        ParameterValue::F32(v) => v.to_le_bytes(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::MessageType;
+    use super::{FlagBits, MessageType};
 
     #[test]
     fn message_type_matches_ulog_spec_bytes() {
         assert_eq!(MessageType::AddSubscription.as_u8(), b'A');
+        assert_eq!(MessageType::RemoveSubscription.as_u8(), b'R');
         assert_eq!(MessageType::TaggedLoggedString.as_u8(), b'C');
         assert_eq!(MessageType::Data.as_u8(), b'D');
         assert_eq!(MessageType::Format.as_u8(), b'F');
@@ -172,5 +501,35 @@ mod tests {
         assert_eq!(MessageType::Parameter.as_u8(), b'P');
         assert_eq!(MessageType::FlagBits.as_u8(), b'B');
         assert_eq!(MessageType::Sync.as_u8(), b'S');
+        assert_eq!(MessageType::Info.as_u8(), b'I');
+        assert_eq!(MessageType::MultiInfo.as_u8(), b'M');
+        assert_eq!(MessageType::ParameterDefault.as_u8(), b'Q');
+        assert_eq!(MessageType::Dropout.as_u8(), b'O');
+    }
+
+    #[test]
+    fn flag_bits_encode_appended_offsets() {
+        let mut flags = FlagBits::new();
+        flags.compat_flags[0] = FlagBits::COMPAT_DEFAULT_PARAMETERS;
+        assert!(!flags.has_appended_data());
+        assert!(flags.push_appended_offset(0x0102));
+        assert!(flags.push_appended_offset(0x0304));
+        assert!(flags.push_appended_offset(0x0506));
+        assert!(!flags.push_appended_offset(0x0708));
+        assert!(flags.has_appended_data());
+
+        let payload = flags.encode();
+        assert_eq!(payload[0], 1);
+        assert_eq!(payload[8], 1);
+        assert_eq!(&payload[16..18], &[2, 1]);
+        assert_eq!(&payload[24..26], &[4, 3]);
+        assert_eq!(&payload[32..34], &[6, 5]);
+    }
+
+    #[test]
+    fn dropout_duration_is_clamped_to_u16_millis() {
+        assert_eq!(super::dropout_duration_ms(1_000, 251_999), 250);
+        assert_eq!(super::dropout_duration_ms(5_000, 1_000), 0);
+        assert_eq!(super::dropout_duration_ms(0, u64::MAX), u16::MAX);
     }
 }
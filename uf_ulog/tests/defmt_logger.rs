@@ -0,0 +1,53 @@
+#![cfg(feature = "defmt")]
+
+use std::cell::RefCell;
+
+use critical_section::Mutex;
+use uf_ulog::{LogLevel, Record, RecordMeta};
+
+static RECORDS: Mutex<RefCell<Vec<Record<32>>>> = Mutex::new(RefCell::new(Vec::new()));
+static FORWARDED: Mutex<RefCell<Vec<u8>>> = Mutex::new(RefCell::new(Vec::new()));
+
+fn clock() -> u64 {
+    42
+}
+
+uf_ulog::ulog_defmt_global_logger!(
+    record_cap = 32,
+    tag = 7,
+    clock = clock,
+    send = |record| critical_section::with(|cs| RECORDS.borrow_ref_mut(cs).push(record)),
+    forward = |bytes: &[u8]| {
+        critical_section::with(|cs| FORWARDED.borrow_ref_mut(cs).extend_from_slice(bytes))
+    },
+);
+
+defmt::timestamp!("{=u64}", 0);
+
+#[test]
+fn defmt_frames_become_tagged_log_records() {
+    // Without `DEFMT_LOG`, defmt only compiles in `error!`.
+    defmt::error!("armed {}", 2u8);
+    defmt::error!("disarmed");
+
+    let records = critical_section::with(|cs| RECORDS.take(cs));
+    if !uf_ulog::__private::static_level_enabled(LogLevel::Info) {
+        assert!(records.is_empty());
+        return;
+    }
+    assert_eq!(records.len(), 2);
+    for record in &records {
+        assert!(!record.bytes().is_empty());
+        assert_eq!(
+            record.meta(),
+            RecordMeta::LoggedString {
+                level: LogLevel::Info,
+                tag: Some(7),
+                ts: 42
+            }
+        );
+    }
+    // The forwarded stream is rzCOBS-encoded and ends with a frame delimiter.
+    let forwarded = critical_section::with(|cs| FORWARDED.take(cs));
+    assert_eq!(forwarded.last(), Some(&0));
+}
@@ -0,0 +1,56 @@
+#![cfg(all(feature = "defmt", feature = "derive"))]
+
+use uf_ulog::{LogLevel, RecordMeta, ULogData, ULogProducer, ULogRegistry};
+
+#[derive(ULogData)]
+struct Sample {
+    timestamp: u64,
+}
+
+#[derive(ULogRegistry)]
+#[allow(dead_code)]
+enum Messages {
+    Sample,
+}
+
+#[defmt::global_logger]
+struct NullLogger;
+
+unsafe impl defmt::Logger for NullLogger {
+    fn acquire() {}
+    unsafe fn flush() {}
+    unsafe fn release() {}
+    unsafe fn write(_bytes: &[u8]) {}
+}
+
+defmt::timestamp!("{=u64}", 0);
+
+// The macros resolve defmt through uf_ulog, so nothing here imports it.
+fn log_armed(producer: &ULogProducer<Messages, 32, 8>, count: u32) -> Option<uf_ulog::Record<32>> {
+    uf_ulog::ulog_defmt_warn!(producer, 3, 10, "armed {}", count)
+}
+
+#[test]
+fn defmt_macros_log_to_ulog() {
+    let producer = ULogProducer::<Messages, 32, 8>::new();
+    let enabled = uf_ulog::__private::static_level_enabled;
+
+    match log_armed(&producer, 2) {
+        Some(record) => {
+            assert_eq!(record.bytes(), b"armed 2");
+            assert_eq!(
+                record.meta(),
+                RecordMeta::LoggedString {
+                    level: LogLevel::Warning,
+                    tag: Some(3),
+                    ts: 10
+                }
+            );
+        }
+        None => assert!(!enabled(LogLevel::Warning)),
+    }
+    assert_eq!(
+        uf_ulog::ulog_defmt_debug!(producer, 3, 11, "x={}", 1u8).is_some(),
+        enabled(LogLevel::Debug)
+    );
+}
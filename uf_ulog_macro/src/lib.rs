@@ -43,6 +43,22 @@ impl PrimitiveKind {
             Self::Bool => "bool",
         }
     }
+
+    fn rust_name(self) -> &'static str {
+        match self {
+            Self::U8 => "u8",
+            Self::I8 => "i8",
+            Self::U16 => "u16",
+            Self::I16 => "i16",
+            Self::U32 => "u32",
+            Self::I32 => "i32",
+            Self::U64 => "u64",
+            Self::I64 => "i64",
+            Self::F32 => "f32",
+            Self::F64 => "f64",
+            Self::Bool => "bool",
+        }
+    }
 }
 
 enum ULogType {
@@ -53,22 +69,39 @@ enum ULogType {
         elem_kind: PrimitiveKind,
         len: usize,
     },
+    String {
+        len: usize,
+    },
+    Nested {
+        path: syn::Path,
+        len: Option<usize>,
+    },
 }
 
 impl ULogType {
-    fn size(&self) -> usize {
+    fn size_tokens(&self) -> proc_macro2::TokenStream {
         match self {
-            Self::Scalar { kind } => kind.size(),
-            Self::Array { elem_kind, len } => elem_kind.size() * len,
+            Self::Scalar { kind } => {
+                let size = kind.size();
+                quote!(#size)
+            }
+            Self::Array { elem_kind, len } => {
+                let size = elem_kind.size() * len;
+                quote!(#size)
+            }
+            Self::String { len } => quote!(#len),
+            Self::Nested { path, len } => {
+                let len = len.unwrap_or(1);
+                quote!(<#path as ::uf_ulog::ULogNested>::WIRE_SIZE * #len)
+            }
         }
     }
+}
 
-    fn format_ulog(&self) -> String {
-        match self {
-            Self::Scalar { kind } => kind.ulog_name().to_string(),
-            Self::Array { elem_kind, len } => format!("{}[{len}]", elem_kind.ulog_name()),
-        }
-    }
+#[derive(Clone, Copy)]
+enum DeriveKind {
+    Data,
+    Nested,
 }
 
 struct FieldInfo {
@@ -85,6 +118,14 @@ pub fn derive_ulog_message(input: TokenStream) -> TokenStream {
         .into()
 }
 
+#[proc_macro_derive(ULogNested, attributes(uf_ulog))]
+pub fn derive_ulog_nested(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_nested_derive(&input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
 #[proc_macro_derive(ULogRegistry, attributes(uf_ulog))]
 pub fn derive_ulog_registry(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
@@ -104,20 +145,47 @@ fn expand_derive(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
     let fields = extract_named_fields(&input.data, ident)?;
     validate_timestamp_field(fields, ident)?;
 
+    expand_fields(input, fields, DeriveKind::Data)
+}
+
+fn expand_nested_derive(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let ident = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "ULogNested does not support structs with generics; remove generic parameters or implement `::uf_ulog::ULogNested` manually",
+        ));
+    }
+    let fields = extract_named_fields(&input.data, ident)?;
+
+    expand_fields(input, fields, DeriveKind::Nested)
+}
+
+fn expand_fields(
+    input: &DeriveInput,
+    fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
+    kind: DeriveKind,
+) -> syn::Result<proc_macro2::TokenStream> {
     let field_infos: Vec<FieldInfo> = fields.iter().map(process_field).collect::<Result<_, _>>()?;
 
-    let format_string = build_format_string(&field_infos);
-    let size_terms: Vec<usize> = field_infos.iter().map(|fi| fi.ty.size()).collect();
+    let format = build_format(&field_infos);
+    let size_terms: Vec<proc_macro2::TokenStream> =
+        field_infos.iter().map(|fi| fi.ty.size_tokens()).collect();
+    let nested = build_nested_metas(&field_infos);
     let encode_body = build_encode_body(&field_infos);
+    let decode_body = build_decode_body(&field_infos);
 
     let message_name = extract_ulog_name(input)?;
 
     Ok(generate_impl(
-        ident,
+        &input.ident,
+        kind,
         &message_name,
-        &format_string,
+        &format,
         &size_terms,
+        &nested,
         &encode_body,
+        &decode_body,
     ))
 }
 
@@ -161,6 +229,11 @@ fn expand_registry_derive(input: &DeriveInput) -> syn::Result<proc_macro2::Token
         message_types.push(type_ident);
     }
 
+    let nested_entries = message_types.iter().map(|ty| {
+        quote! {
+            <#ty as ::uf_ulog::ULogData>::NESTED
+        }
+    });
     let meta_entries = message_types.iter().map(|ty| {
         quote! {
             ::uf_ulog::MessageMeta {
@@ -182,9 +255,10 @@ fn expand_registry_derive(input: &DeriveInput) -> syn::Result<proc_macro2::Token
 
     Ok(quote! {
         impl ::uf_ulog::ULogRegistry for #enum_ident {
-            const REGISTRY: ::uf_ulog::Registry = ::uf_ulog::Registry::new(&[
-                #(#meta_entries),*
-            ]);
+            const REGISTRY: ::uf_ulog::Registry = ::uf_ulog::Registry::with_nested(
+                &[#(#meta_entries),*],
+                &[#(#nested_entries),*],
+            );
         }
 
         #(#topic_impls)*
@@ -258,7 +332,11 @@ fn process_field(field: &syn::Field) -> Result<FieldInfo, syn::Error> {
         .ident
         .as_ref()
         .ok_or_else(|| syn::Error::new_spanned(field, "expected named field"))?;
-    let ty = type_spec(&field.ty)?;
+    let ty = if is_string_field(field)? {
+        string_type_spec(&field.ty)?
+    } else {
+        type_spec(&field.ty)?
+    };
 
     Ok(FieldInfo {
         ident: field_name.clone(),
@@ -267,27 +345,134 @@ fn process_field(field: &syn::Field) -> Result<FieldInfo, syn::Error> {
     })
 }
 
-fn build_format_string(field_infos: &[FieldInfo]) -> String {
+fn is_string_field(field: &syn::Field) -> syn::Result<bool> {
+    let mut string = false;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("uf_ulog"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("string") {
+                if string {
+                    return Err(meta.error("duplicate `string` in `#[uf_ulog(...)]`"));
+                }
+                string = true;
+                return Ok(());
+            }
+            Err(meta.error("unsupported uf_ulog field attribute key, expected `string`"))
+        })?;
+    }
+    Ok(string)
+}
+
+fn string_type_spec(ty: &Type) -> syn::Result<ULogType> {
+    if let Type::Array(array_ty) = ty {
+        if primitive_type_name(&array_ty.elem).is_some_and(|ident| ident == "u8") {
+            let len = extract_array_len(&array_ty.len)?;
+            return Ok(ULogType::String { len });
+        }
+    }
+    Err(syn::Error::new_spanned(
+        ty,
+        "`#[uf_ulog(string)]` requires a `[u8; N]` field",
+    ))
+}
+
+fn build_format(field_infos: &[FieldInfo]) -> proc_macro2::TokenStream {
+    let mut parts = Vec::new();
+    let mut literal = String::new();
+    for fi in field_infos {
+        match &fi.ty {
+            ULogType::Scalar { kind } => {
+                literal.push_str(&format!("{} {};", kind.ulog_name(), fi.name));
+            }
+            ULogType::Array { elem_kind, len } => {
+                literal.push_str(&format!("{}[{len}] {};", elem_kind.ulog_name(), fi.name));
+            }
+            ULogType::String { len } => {
+                literal.push_str(&format!("char[{len}] {};", fi.name));
+            }
+            ULogType::Nested { path, len } => {
+                if !literal.is_empty() {
+                    parts.push(quote!(#literal));
+                    literal.clear();
+                }
+                parts.push(quote!(<#path as ::uf_ulog::ULogNested>::NAME));
+                match len {
+                    Some(len) => literal.push_str(&format!("[{len}] {};", fi.name)),
+                    None => literal.push_str(&format!(" {};", fi.name)),
+                }
+            }
+        }
+    }
+
+    if parts.is_empty() {
+        return quote!(#literal);
+    }
+    if !literal.is_empty() {
+        parts.push(quote!(#literal));
+    }
+    quote! {
+        {
+            const PARTS: &[&str] = &[#(#parts),*];
+            const LEN: usize = ::uf_ulog::__private::concat_len(PARTS);
+            const BYTES: [u8; LEN] = ::uf_ulog::__private::concat::<LEN>(PARTS);
+            ::uf_ulog::__private::as_str(&BYTES)
+        }
+    }
+}
+
+fn build_nested_metas(field_infos: &[FieldInfo]) -> Vec<proc_macro2::TokenStream> {
+    let mut seen = BTreeSet::new();
     field_infos
         .iter()
-        .map(|fi| format!("{} {};", fi.ty.format_ulog(), fi.name))
-        .collect::<String>()
+        .filter_map(|fi| match &fi.ty {
+            ULogType::Nested { path, .. } if seen.insert(quote!(#path).to_string()) => {
+                Some(quote!(<#path as ::uf_ulog::ULogNested>::META))
+            }
+            _ => None,
+        })
+        .collect()
 }
 
+#[allow(clippy::too_many_arguments)]
 fn generate_impl(
     ident: &syn::Ident,
+    kind: DeriveKind,
     message_name: &LitStr,
-    format_string: &str,
-    size_terms: &[usize],
+    format: &proc_macro2::TokenStream,
+    size_terms: &[proc_macro2::TokenStream],
+    nested: &[proc_macro2::TokenStream],
     encode_body: &[proc_macro2::TokenStream],
+    decode_body: &[proc_macro2::TokenStream],
 ) -> proc_macro2::TokenStream {
-    let format_lit = LitStr::new(format_string, ident.span());
+    let (trait_path, timestamp_fn) = match kind {
+        DeriveKind::Data => (
+            quote!(::uf_ulog::ULogData),
+            quote! {
+                #[inline]
+                fn timestamp(&self) -> u64 {
+                    self.timestamp
+                }
+            },
+        ),
+        DeriveKind::Nested => (quote!(::uf_ulog::ULogNested), quote!()),
+    };
+    let nested_const = if nested.is_empty() {
+        quote!()
+    } else {
+        quote! {
+            const NESTED: &'static [::uf_ulog::NestedMeta] = &[#(#nested),*];
+        }
+    };
 
     quote! {
-        impl ::uf_ulog::ULogData for #ident {
+        impl #trait_path for #ident {
             const NAME: &'static str = #message_name;
             const WIRE_SIZE: usize = 0 #(+ #size_terms)*;
-            const FORMAT: &'static str = #format_lit;
+            const FORMAT: &'static str = #format;
+            #nested_const
 
             #[inline]
             fn encode(&self, buf: &mut [u8]) -> ::core::result::Result<usize, ::uf_ulog::EncodeError> {
@@ -298,9 +483,16 @@ fn generate_impl(
                 #(#encode_body)*
                 Ok(Self::WIRE_SIZE)
             }
+            #timestamp_fn
             #[inline]
-            fn timestamp(&self) -> u64 {
-                self.timestamp
+            fn decode(buf: &[u8]) -> ::core::result::Result<Self, ::uf_ulog::DecodeError> {
+                if buf.len() < Self::WIRE_SIZE {
+                    return Err(::uf_ulog::DecodeError::BufferTooShort);
+                }
+                let mut offset = 0usize;
+                Ok(Self {
+                    #(#decode_body)*
+                })
             }
         }
     }
@@ -323,11 +515,121 @@ fn build_encode_body(field_infos: &[FieldInfo]) -> Vec<proc_macro2::TokenStream>
                         }
                     }
                 }
+                ULogType::String { len } => quote! {
+                    buf[offset..offset + #len].copy_from_slice(&self.#field_ident);
+                    offset += #len;
+                },
+                ULogType::Nested { len: None, .. } => quote! {
+                    offset += ::uf_ulog::ULogNested::encode(&self.#field_ident, &mut buf[offset..])?;
+                },
+                ULogType::Nested { len: Some(_), .. } => quote! {
+                    for value in &self.#field_ident {
+                        offset += ::uf_ulog::ULogNested::encode(value, &mut buf[offset..])?;
+                    }
+                },
             }
         })
         .collect()
 }
 
+fn build_decode_body(field_infos: &[FieldInfo]) -> Vec<proc_macro2::TokenStream> {
+    field_infos
+        .iter()
+        .map(|field| {
+            let field_ident = &field.ident;
+            let value_tokens = match field.ty {
+                ULogType::Scalar { kind } => decode_primitive_tokens(kind),
+                ULogType::Array { elem_kind, len } => {
+                    let elem_ty = syn::Ident::new(elem_kind.rust_name(), field_ident.span());
+                    let elem_tokens = decode_primitive_tokens(elem_kind);
+                    quote! {
+                        {
+                            let mut values = [<#elem_ty as ::core::default::Default>::default(); #len];
+                            for value in &mut values {
+                                *value = #elem_tokens;
+                            }
+                            values
+                        }
+                    }
+                }
+                ULogType::String { len } => quote! {
+                    {
+                        let mut value = [0u8; #len];
+                        value.copy_from_slice(&buf[offset..offset + #len]);
+                        offset += #len;
+                        value
+                    }
+                },
+                ULogType::Nested { ref path, len: None } => quote! {
+                    {
+                        let value = <#path as ::uf_ulog::ULogNested>::decode(&buf[offset..])?;
+                        offset += <#path as ::uf_ulog::ULogNested>::WIRE_SIZE;
+                        value
+                    }
+                },
+                ULogType::Nested {
+                    ref path,
+                    len: Some(len),
+                } => quote! {
+                    {
+                        let value =
+                            ::uf_ulog::__private::decode_nested_array::<#path, #len>(&buf[offset..])?;
+                        offset += <#path as ::uf_ulog::ULogNested>::WIRE_SIZE * #len;
+                        value
+                    }
+                },
+            };
+            quote! { #field_ident: #value_tokens, }
+        })
+        .collect()
+}
+
+fn decode_primitive_tokens(kind: PrimitiveKind) -> proc_macro2::TokenStream {
+    match kind {
+        PrimitiveKind::U8 => quote! {
+            {
+                let value = buf[offset];
+                offset += 1;
+                value
+            }
+        },
+        PrimitiveKind::I8 => quote! {
+            {
+                let value = buf[offset] as i8;
+                offset += 1;
+                value
+            }
+        },
+        PrimitiveKind::Bool => quote! {
+            {
+                let value = buf[offset] != 0;
+                offset += 1;
+                value
+            }
+        },
+        PrimitiveKind::U16
+        | PrimitiveKind::I16
+        | PrimitiveKind::U32
+        | PrimitiveKind::I32
+        | PrimitiveKind::U64
+        | PrimitiveKind::I64
+        | PrimitiveKind::F32
+        | PrimitiveKind::F64 => {
+            let ty = syn::Ident::new(kind.rust_name(), proc_macro2::Span::call_site());
+            let size = kind.size();
+            quote! {
+                {
+                    let mut bytes = [0u8; #size];
+                    let end = offset + #size;
+                    bytes.copy_from_slice(&buf[offset..end]);
+                    offset = end;
+                    #ty::from_le_bytes(bytes)
+                }
+            }
+        }
+    }
+}
+
 fn encode_primitive_tokens(
     value_tokens: proc_macro2::TokenStream,
     kind: PrimitiveKind,
@@ -406,29 +708,50 @@ fn primitive_spec(ident: &syn::Ident) -> Option<PrimitiveKind> {
 fn type_spec(ty: &Type) -> Result<ULogType, syn::Error> {
     match ty {
         Type::Array(array_ty) => parse_array_type(array_ty),
-        Type::Path(type_path) if is_simple_path(type_path) => parse_primitive_type(type_path),
+        Type::Path(type_path) => {
+            if let Some(kind) = primitive_type_name(ty).and_then(primitive_spec) {
+                return Ok(ULogType::Scalar { kind });
+            }
+            let path = nested_type_path(type_path).ok_or_else(|| unsupported_type_error(ty))?;
+            Ok(ULogType::Nested {
+                path: path.clone(),
+                len: None,
+            })
+        }
         _ => Err(unsupported_type_error(ty)),
     }
 }
 
-fn parse_primitive_type(type_path: &TypePath) -> Result<ULogType, syn::Error> {
-    let Some(ident) = primitive_ident_from_path(type_path) else {
-        return Err(unsupported_type_error(&Type::Path(type_path.clone())));
-    };
-    let Some(kind) = primitive_spec(ident) else {
-        return Err(unsupported_type_error(&Type::Path(type_path.clone())));
-    };
-    Ok(ULogType::Scalar { kind })
-}
-
 fn parse_array_type(array_ty: &TypeArray) -> Result<ULogType, syn::Error> {
-    let elem_ident = primitive_type_name(&array_ty.elem)
-        .ok_or_else(|| syn::Error::new_spanned(&array_ty.elem, "unsupported array element type"))?;
-    let elem_kind = primitive_spec(elem_ident)
-        .ok_or_else(|| syn::Error::new_spanned(&array_ty.elem, "unsupported array element type"))?;
     let len = extract_array_len(&array_ty.len)?;
+    if let Some(elem_kind) = primitive_type_name(&array_ty.elem).and_then(primitive_spec) {
+        return Ok(ULogType::Array { elem_kind, len });
+    }
 
-    Ok(ULogType::Array { elem_kind, len })
+    let path = match array_ty.elem.as_ref() {
+        Type::Path(type_path) => nested_type_path(type_path),
+        _ => None,
+    }
+    .ok_or_else(|| syn::Error::new_spanned(&array_ty.elem, "unsupported array element type"))?;
+    Ok(ULogType::Nested {
+        path: path.clone(),
+        len: Some(len),
+    })
+}
+
+// Any other plain type path is assumed to implement `ULogNested`.
+fn nested_type_path(type_path: &TypePath) -> Option<&syn::Path> {
+    let has_arguments = type_path
+        .path
+        .segments
+        .iter()
+        .any(|segment| !segment.arguments.is_none());
+    let last = type_path.path.segments.last()?;
+    let builtin = matches!(
+        last.ident.to_string().as_str(),
+        "char" | "str" | "usize" | "isize" | "u128" | "i128" | "String"
+    );
+    (type_path.qself.is_none() && !has_arguments && !builtin).then_some(&type_path.path)
 }
 
 fn unsupported_type_error(ty: &Type) -> syn::Error {
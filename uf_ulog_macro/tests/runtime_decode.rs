@@ -0,0 +1,74 @@
+use uf_ulog::{DecodeError, TopicOf, ULogData, ULogRegistry};
+
+#[derive(Debug, PartialEq, uf_ulog_macro::ULogData)]
+struct Gyro {
+    timestamp: u64,
+    x: f32,
+    y: f32,
+    z: f32,
+}
+
+#[derive(Debug, PartialEq, uf_ulog_macro::ULogData)]
+struct RcInput {
+    timestamp: u64,
+    values: [u16; 4],
+    rssi: i8,
+    failsafe: bool,
+}
+
+#[derive(uf_ulog_macro::ULogRegistry)]
+pub enum Messages {
+    Gyro,
+    RcInput,
+}
+
+#[test]
+fn decode_round_trips_encode() {
+    let gyro = Gyro {
+        timestamp: 0x0807_0605_0403_0201,
+        x: 1.0,
+        y: -2.5,
+        z: 0.5,
+    };
+    let mut buf = [0u8; Gyro::WIRE_SIZE];
+    gyro.encode(&mut buf).unwrap();
+    assert_eq!(Gyro::decode(&buf), Ok(gyro));
+
+    let rc = RcInput {
+        timestamp: 7,
+        values: [0x1122, 0x3344, 0x5566, 0x7788],
+        rssi: -2,
+        failsafe: true,
+    };
+    let mut buf = [0u8; RcInput::WIRE_SIZE];
+    rc.encode(&mut buf).unwrap();
+    assert_eq!(RcInput::decode(&buf), Ok(rc));
+
+    assert_eq!(
+        RcInput::decode(&buf[..RcInput::WIRE_SIZE - 1]),
+        Err(DecodeError::BufferTooShort)
+    );
+}
+
+#[test]
+fn dispatch_by_subscription_name() {
+    let rc = RcInput {
+        timestamp: 1,
+        values: [1, 2, 3, 4],
+        rssi: 0,
+        failsafe: false,
+    };
+    let mut buf = [0u8; RcInput::WIRE_SIZE];
+    rc.encode(&mut buf).unwrap();
+
+    let topic_index = Messages::REGISTRY.index_of(RcInput::NAME).unwrap();
+    assert_eq!(
+        <Gyro as TopicOf<Messages>>::TOPIC.decode(topic_index, &buf),
+        None
+    );
+    assert_eq!(
+        <RcInput as TopicOf<Messages>>::TOPIC.decode(topic_index, &buf),
+        Some(Ok(rc))
+    );
+    assert_eq!(Messages::REGISTRY.index_of("missing"), None);
+}
@@ -1,4 +1,4 @@
-use uf_ulog::{EncodeError, ULogData};
+use uf_ulog::{fixed_str, EncodeError, ULogData};
 
 #[derive(uf_ulog_macro::ULogData)]
 struct Gyro {
@@ -16,6 +16,13 @@ struct RcInput {
     failsafe: bool,
 }
 
+#[derive(uf_ulog_macro::ULogData)]
+struct FlightMode {
+    timestamp: u64,
+    #[uf_ulog(string)]
+    name: [u8; 8],
+}
+
 #[test]
 fn encode_scalars() {
     let msg = Gyro {
@@ -53,3 +60,18 @@ fn encode_arrays_and_single_byte_types() {
         [1, 2, 3, 4, 5, 6, 7, 8, 0x22, 0x11, 0x44, 0x33, 0x66, 0x55, 0x88, 0x77, 0xFE, 1,]
     );
 }
+
+#[test]
+fn encode_zero_padded_string() {
+    let msg = FlightMode {
+        timestamp: 1,
+        name: fixed_str("POSCTL"),
+    };
+
+    let mut buf = [0u8; FlightMode::WIRE_SIZE];
+    msg.encode(&mut buf).unwrap();
+
+    assert_eq!(FlightMode::FORMAT, "uint64_t timestamp;char[8] name;");
+    assert_eq!(&buf[8..], b"POSCTL\0\0");
+    assert_eq!(fixed_str::<4>("AUTO.MISSION"), *b"AUTO");
+}
@@ -0,0 +1,90 @@
+use uf_ulog::{NestedMeta, ULogData, ULogNested, ULogRegistry};
+
+#[derive(Debug, PartialEq, uf_ulog_macro::ULogNested)]
+#[uf_ulog(name = "quaternion_s")]
+struct Quaternion {
+    q: [f32; 4],
+}
+
+#[derive(Debug, PartialEq, uf_ulog_macro::ULogNested)]
+#[uf_ulog(name = "pose_s")]
+struct Pose {
+    attitude: Quaternion,
+    valid: bool,
+}
+
+#[derive(Debug, PartialEq, uf_ulog_macro::ULogData)]
+#[uf_ulog(name = "vehicle_attitude")]
+struct VehicleAttitude {
+    timestamp: u64,
+    attitude: Quaternion,
+    history: [Quaternion; 2],
+    pose: Pose,
+}
+
+#[derive(Debug, PartialEq, uf_ulog_macro::ULogData)]
+#[uf_ulog(name = "vehicle_pose")]
+struct VehiclePose {
+    timestamp: u64,
+    pose: Pose,
+    attitude: Quaternion,
+}
+
+#[derive(uf_ulog_macro::ULogRegistry)]
+pub enum Messages {
+    VehicleAttitude,
+    VehiclePose,
+}
+
+fn quaternion(w: f32) -> Quaternion {
+    Quaternion {
+        q: [w, 0.0, 0.0, 0.0],
+    }
+}
+
+#[test]
+fn nested_format_and_size() {
+    assert_eq!(Quaternion::FORMAT, "float[4] q;");
+    assert_eq!(Pose::FORMAT, "quaternion_s attitude;bool valid;");
+    assert_eq!(
+        VehicleAttitude::FORMAT,
+        "uint64_t timestamp;quaternion_s attitude;quaternion_s[2] history;pose_s pose;"
+    );
+    assert_eq!(VehicleAttitude::WIRE_SIZE, 8 + 16 + 32 + 17);
+    assert_eq!(
+        VehicleAttitude::NESTED,
+        &[Quaternion::META, Pose::META] as &[NestedMeta]
+    );
+}
+
+#[test]
+fn nested_formats_are_deduplicated_in_registry() {
+    let names: Vec<_> = Messages::REGISTRY
+        .nested_formats()
+        .map(|meta| meta.name)
+        .collect();
+    assert_eq!(names, ["quaternion_s", "pose_s"]);
+}
+
+#[test]
+fn nested_encode_decode_inline() {
+    let msg = VehicleAttitude {
+        timestamp: 1,
+        attitude: quaternion(1.0),
+        history: [quaternion(0.5), quaternion(0.25)],
+        pose: Pose {
+            attitude: quaternion(-1.0),
+            valid: true,
+        },
+    };
+
+    let mut buf = [0u8; VehicleAttitude::WIRE_SIZE];
+    assert_eq!(msg.encode(&mut buf), Ok(VehicleAttitude::WIRE_SIZE));
+    assert_eq!(&buf[8..12], &1.0f32.to_le_bytes());
+    assert_eq!(&buf[24..28], &0.5f32.to_le_bytes());
+    assert_eq!(&buf[40..44], &0.25f32.to_le_bytes());
+    assert_eq!(&buf[56..60], &(-1.0f32).to_le_bytes());
+    assert_eq!(buf[72], 1);
+
+    assert_eq!(VehicleAttitude::decode(&buf), Ok(msg));
+}
@@ -0,0 +1,37 @@
+mod a {
+    #[derive(uf_ulog_macro::ULogNested)]
+    #[uf_ulog(name = "point_s")]
+    pub struct Point {
+        pub x: f32,
+    }
+}
+
+mod b {
+    #[derive(uf_ulog_macro::ULogNested)]
+    #[uf_ulog(name = "point_s")]
+    pub struct Point {
+        pub x: u8,
+    }
+}
+
+#[derive(uf_ulog_macro::ULogData)]
+struct First {
+    timestamp: u64,
+    point: a::Point,
+}
+
+#[derive(uf_ulog_macro::ULogData)]
+struct Second {
+    timestamp: u64,
+    point: b::Point,
+}
+
+#[derive(uf_ulog_macro::ULogRegistry)]
+enum Topics {
+    First,
+    Second,
+}
+
+fn main() {
+    let _ = <Topics as uf_ulog::ULogRegistry>::REGISTRY;
+}
@@ -0,0 +1,8 @@
+#[derive(uf_ulog_macro::ULogData)]
+struct NonByteString {
+    timestamp: u64,
+    #[uf_ulog(string)]
+    name: [u16; 4],
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use uf_ulog::ULogData;
+
+#[derive(uf_ulog_macro::ULogData)]
+struct FlightMode {
+    timestamp: u64,
+    #[uf_ulog(string)]
+    name: [u8; 16],
+}
+
+fn main() {
+    assert_eq!(FlightMode::FORMAT, "uint64_t timestamp;char[16] name;");
+    assert_eq!(FlightMode::WIRE_SIZE, 24);
+}